@@ -0,0 +1,27 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use powerset::Powerset;
+
+fn sparse_subset(c: &mut Criterion) {
+    let items = (0..63).collect::<Vec<u32>>();
+    let subset = items.subset_at(1 << 3 | 1 << 60).unwrap();
+
+    c.bench_function("sparse subset of 63 elements", |b| {
+        b.iter(|| black_box(subset).sum::<u32>())
+    });
+}
+
+fn dense_subsets(c: &mut Criterion) {
+    let items = (0..16).collect::<Vec<u32>>();
+
+    c.bench_function("all subsets of 16 elements", |b| {
+        b.iter(|| {
+            black_box(&items)
+                .powerset()
+                .map(|subset| subset.sum::<u32>())
+                .sum::<u32>()
+        })
+    });
+}
+
+criterion_group!(benches, sparse_subset, dense_subsets);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+//! Implements `#[derive(SizableContainer)]` for the `powerset` crate.
+//!
+//! The number of elements is the `len()` of the field marked with `#[powerset(len)]`:
+//!
+//! ```ignore
+//! #[derive(SizableContainer)]
+//! struct Scores {
+//!     #[powerset(len)]
+//!     values: Vec<u32>,
+//!     name: String,
+//! }
+//! ```
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Index, Member};
+
+/// Implement `SizableContainer` by delegating to the `len()` of the field marked with
+/// `#[powerset(len)]`.
+#[proc_macro_derive(SizableContainer, attributes(powerset))]
+pub fn derive_sizable_container(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match len_field(&input) {
+        Ok(field) => {
+            let name = &input.ident;
+            let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+            quote! {
+                impl #impl_generics ::powerset::SizableContainer for #name #ty_generics
+                    #where_clause
+                {
+                    fn num_elements(&self) -> usize {
+                        self.#field.len()
+                    }
+                }
+            }
+            .into()
+        }
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Find the one field marked with `#[powerset(len)]`
+fn len_field(input: &DeriveInput) -> syn::Result<Member> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "SizableContainer can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut marked = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("powerset")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("len") {
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `len`"))
+                }
+            })?;
+
+            marked.push(match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i)),
+            });
+        }
+    }
+
+    match marked.len() {
+        1 => Ok(marked.remove(0)),
+        0 => Err(Error::new(
+            Span::call_site(),
+            match fields {
+                Fields::Unit => "SizableContainer cannot be derived for unit structs",
+                _ => "mark the field holding the elements with `#[powerset(len)]`",
+            },
+        )),
+        _ => Err(Error::new(
+            Span::call_site(),
+            "only one field can be marked with `#[powerset(len)]`",
+        )),
+    }
+}
@@ -0,0 +1,53 @@
+use std::ops::Index;
+
+use powerset::{Powerset, SizableContainer as _};
+use powerset_derive::SizableContainer;
+
+#[derive(SizableContainer)]
+struct Scores {
+    name: &'static str,
+    #[powerset(len)]
+    values: Vec<u32>,
+}
+
+impl Index<usize> for Scores {
+    type Output = u32;
+
+    fn index(&self, index: usize) -> &u32 {
+        &self.values[index]
+    }
+}
+
+#[derive(SizableContainer)]
+struct Wrapper<T>(#[powerset(len)] Vec<T>);
+
+impl<T> Index<usize> for Wrapper<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.0[index]
+    }
+}
+
+#[test]
+fn named_field() {
+    let scores = Scores {
+        name: "scores",
+        values: vec![1, 2, 3],
+    };
+    assert_eq!("scores", scores.name);
+    assert_eq!(3, scores.num_elements());
+
+    let sums = scores
+        .powerset()
+        .map(|subset| subset.sum::<u32>())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![0, 1, 2, 3, 3, 4, 5, 6], sums);
+}
+
+#[test]
+fn tuple_field() {
+    let wrapper = Wrapper(vec!['a', 'b']);
+    assert_eq!(2, wrapper.num_elements());
+    assert_eq!(4, wrapper.powerset().count());
+}
@@ -0,0 +1,356 @@
+//! Containers that adapt other types, so their powerset can be taken.
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::{Index, Range};
+
+use crate::SizableContainer;
+#[cfg(feature = "alloc")]
+use crate::Subset;
+
+/// Makes a container that is indexed by some other type than `usize` usable with
+/// [`Powerset`](crate::Powerset), by converting the positions into the index type of the
+/// container.
+///
+/// ```
+///     use core::ops::Index;
+///     use crate::powerset::{IndexAdapter, Powerset, SizableContainer};
+///
+///     struct Id(usize);
+///
+///     impl From<usize> for Id {
+///         fn from(id: usize) -> Self {
+///             Id(id)
+///         }
+///     }
+///
+///     struct Names(Vec<&'static str>);
+///
+///     impl Index<Id> for Names {
+///         type Output = &'static str;
+///         fn index(&self, id: Id) -> &Self::Output {
+///             &self.0[id.0]
+///         }
+///     }
+///
+///     impl SizableContainer for Names {
+///         fn num_elements(&self) -> usize {
+///             self.0.len()
+///         }
+///     }
+///
+///     let names = Names(vec!["a", "b", "c"]);
+///     let names = IndexAdapter::<_, Id>::new(&names);
+///     assert_eq!(8, names.powerset().count());
+/// ```
+pub struct IndexAdapter<'a, C: ?Sized, Idx> {
+    items: &'a C,
+    index: PhantomData<fn(usize) -> Idx>,
+}
+
+impl<'a, C: Index<Idx> + SizableContainer + ?Sized, Idx: From<usize>> IndexAdapter<'a, C, Idx> {
+    /// Wrap `items`, which is indexed by `Idx`.
+    pub fn new(items: &'a C) -> Self {
+        IndexAdapter {
+            items,
+            index: PhantomData,
+        }
+    }
+}
+
+impl<'a, C: Index<Idx> + ?Sized, Idx: From<usize>> Index<usize> for IndexAdapter<'a, C, Idx> {
+    type Output = C::Output;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.items[Idx::from(index)]
+    }
+}
+
+impl<'a, C: SizableContainer + ?Sized, Idx> SizableContainer for IndexAdapter<'a, C, Idx> {
+    fn num_elements(&self) -> usize {
+        self.items.num_elements()
+    }
+}
+
+/// A container of the integers in a range, so the powerset of e.g. `0..5` can be taken without
+/// collecting it first.
+///
+/// `Index` has to hand out references, so the values are stored inline. Subsets are limited
+/// to [`max_supported_elements`](crate::max_supported_elements) elements anyway, so this never needs to allocate.
+///
+/// ```
+///     use crate::powerset::{Powerset, RangeContainer};
+///     let range = RangeContainer::from(2..5);
+///
+///     let subset = range.powerset().nth(0b101).unwrap();
+///     assert!(subset.copied().eq([2, 4]));
+/// ```
+pub struct RangeContainer {
+    values: [usize; usize::BITS as usize],
+    len: usize,
+}
+
+impl RangeContainer {
+    /// Panics if the range has more than
+    /// [`max_supported_elements`](crate::max_supported_elements) elements, as its powerset
+    /// could not be taken anyway.
+    pub fn new(range: Range<usize>) -> Self {
+        let len = range.len();
+        crate::assert_supported::<usize>(len);
+
+        let mut values = [0; usize::BITS as usize];
+        for (value, i) in values.iter_mut().zip(range) {
+            *value = i;
+        }
+
+        RangeContainer { values, len }
+    }
+}
+
+impl From<Range<usize>> for RangeContainer {
+    fn from(range: Range<usize>) -> Self {
+        RangeContainer::new(range)
+    }
+}
+
+impl Index<usize> for RangeContainer {
+    type Output = usize;
+    fn index(&self, index: usize) -> &usize {
+        &self.values[..self.len][index]
+    }
+}
+
+impl SizableContainer for RangeContainer {
+    fn num_elements(&self) -> usize {
+        self.len
+    }
+}
+
+/// A container of all the subsets of a container, in the order of
+/// [`Powerset::powerset`](crate::Powerset::powerset), returned from
+/// [`Powerset::powerset_container`](crate::Powerset::powerset_container).
+///
+/// `Index` has to hand out references, so the subsets are collected up front. This makes it
+/// possible to take the powerset of the powerset, of containers of up to 5 elements.
+#[cfg(feature = "alloc")]
+pub struct PowersetContainer<'a, I: Index<usize> + ?Sized> {
+    subsets: Vec<Subset<'a, I>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I: Index<usize> + ?Sized> PowersetContainer<'a, I> {
+    pub(crate) fn new(subsets: Vec<Subset<'a, I>>) -> Self {
+        PowersetContainer { subsets }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I: Index<usize> + ?Sized> Index<usize> for PowersetContainer<'a, I> {
+    type Output = Subset<'a, I>;
+    fn index(&self, index: usize) -> &Subset<'a, I> {
+        &self.subsets[index]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I: Index<usize> + ?Sized> SizableContainer for PowersetContainer<'a, I> {
+    fn num_elements(&self) -> usize {
+        self.subsets.len()
+    }
+}
+
+/// A view of the elements of a `BTreeSet` in sorted order, so the powerset of the set can be
+/// taken.
+///
+/// The view borrows the set and takes a snapshot of the order of its elements, so element `i`
+/// of every subset is the `i`th smallest element of the set.
+///
+/// ```
+///     use std::collections::BTreeSet;
+///     use crate::powerset::{Powerset, ToSortedView};
+///     let set = [3, 1, 2].iter().copied().collect::<BTreeSet<i32>>();
+///
+///     for subset in set.sorted_view().powerset() {
+///         println!("{}", subset);
+///     }
+///
+///     let view = set.sorted_view();
+///     let subset = view.powerset().nth(0b110).unwrap();
+///     assert_eq!(vec![2, 3], subset.to_vec());
+/// ```
+#[cfg(feature = "alloc")]
+pub struct SortedView<'a, T> {
+    items: Vec<&'a T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> SortedView<'a, T> {
+    /// Take a snapshot of the order of the elements of `set`.
+    pub fn new(set: &'a BTreeSet<T>) -> Self {
+        SortedView {
+            items: set.iter().collect(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> Index<usize> for SortedView<'a, T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        self.items[index]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> SizableContainer for SortedView<'a, T> {
+    fn num_elements(&self) -> usize {
+        self.items.len()
+    }
+}
+
+/// Sets that can be viewed as a container of their elements in sorted order.
+#[cfg(feature = "alloc")]
+pub trait ToSortedView<T> {
+    /// A view of the elements in sorted order, which the powerset can be taken of.
+    fn sorted_view(&self) -> SortedView<'_, T>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ToSortedView<T> for BTreeSet<T> {
+    fn sorted_view(&self) -> SortedView<'_, T> {
+        SortedView::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use core::ops::Index;
+
+    use crate::RangeContainer;
+    #[cfg(feature = "alloc")]
+    use crate::{IndexAdapter, Powerset, SizableContainer};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sorted_view() {
+        use std::collections::BTreeSet;
+
+        use crate::ToSortedView;
+
+        let set = [5, -1, 3, 8].iter().copied().collect::<BTreeSet<i32>>();
+        let view = set.sorted_view();
+        assert_eq!(4, view.num_elements());
+
+        for subset in view.powerset() {
+            let elements = subset.cloned().collect::<Vec<i32>>();
+            assert!(elements.windows(2).all(|w| w[0] < w[1]));
+        }
+        assert_eq!(
+            vec![-1, 5, 8],
+            view.powerset().nth(0b1101).unwrap().to_vec()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn powerset_of_powerset() {
+        let items = ['a', 'b'];
+        let container = items.powerset_container();
+        assert_eq!(4, container.num_elements());
+        assert_eq!(vec!['a', 'b'], container[3].to_vec());
+
+        let families = container.powerset().collect::<Vec<_>>();
+        assert_eq!(16, families.len());
+
+        let family = &families[0b1010];
+        assert_eq!(
+            vec![vec!['a'], vec!['a', 'b']],
+            (*family).map(|subset| subset.to_vec()).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    struct Key(usize);
+
+    #[cfg(feature = "alloc")]
+    impl From<usize> for Key {
+        fn from(key: usize) -> Self {
+            Key(key)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    struct Keyed(Vec<i32>);
+
+    #[cfg(feature = "alloc")]
+    impl Index<Key> for Keyed {
+        type Output = i32;
+        fn index(&self, key: Key) -> &i32 {
+            &self.0[key.0]
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl SizableContainer for Keyed {
+        fn num_elements(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn custom_index() {
+        let items = vec![1, 2, 3, 4];
+        let keyed = Keyed(items.clone());
+        let keyed = IndexAdapter::<_, Key>::new(&keyed);
+
+        assert_eq!(
+            items
+                .powerset()
+                .map(|subset| subset.to_vec())
+                .collect::<Vec<_>>(),
+            keyed
+                .powerset()
+                .map(|subset| subset.to_vec())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn range_powerset() {
+        let range = RangeContainer::from(0..3);
+
+        assert_eq!(
+            vec![
+                vec![],
+                vec![0],
+                vec![1],
+                vec![0, 1],
+                vec![2],
+                vec![0, 2],
+                vec![1, 2],
+                vec![0, 1, 2]
+            ],
+            range
+                .powerset()
+                .map(|subset| subset.to_vec())
+                .collect::<Vec<_>>()
+        );
+
+        let range = RangeContainer::from(10..15);
+        assert_eq!(32, range.powerset().count());
+        assert_eq!(
+            vec![10, 11, 12, 13, 14],
+            range.powerset().next_back().unwrap().to_vec()
+        );
+        assert_eq!(0, RangeContainer::from(4..4).num_elements());
+    }
+
+    #[test]
+    #[should_panic]
+    fn range_out_of_bounds() {
+        let range = RangeContainer::from(0..3);
+        let _ = range[3];
+    }
+}
@@ -0,0 +1,214 @@
+//! Iteration over the powerset of containers with too many elements for a `usize` bitmask.
+use core::ops::Index;
+
+use num_bigint::BigUint;
+
+use crate::SizableContainer;
+
+/// The iterator returned from [`Powerset::powerset_big`](crate::Powerset::powerset_big).
+///
+/// Works like [`SubsetIterator`](crate::SubsetIterator), but the bitmask is a `BigUint`, so
+/// the container can have any number of elements.
+pub struct BigPowersetIterator<'a, I: Index<usize> + ?Sized>
+where
+    I::Output: Sized,
+{
+    items: &'a I,
+    subset: BigUint,
+    end: BigUint,
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> BigPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    pub(crate) fn new(items: &'a I) -> Self {
+        BigPowersetIterator {
+            items,
+            subset: BigUint::default(),
+            end: BigUint::from(1u32) << items.num_elements(),
+        }
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> Iterator for BigPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    type Item = BigSubset<'a, I>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.subset >= self.end {
+            return None;
+        }
+
+        let subset = BigSubset::new(self.items, self.subset.clone());
+        self.subset += 1u32;
+
+        Some(subset)
+    }
+}
+
+/// The iterator returned from
+/// [`Powerset::combinations_big`](crate::Powerset::combinations_big).
+///
+/// Works like [`CombinationsIterator`](crate::CombinationsIterator), but the bitmask is a
+/// `BigUint`, so the container can have any number of elements.
+pub struct BigCombinationsIterator<'a, I: Index<usize> + ?Sized>
+where
+    I::Output: Sized,
+{
+    items: &'a I,
+    subset: BigUint,
+    end: BigUint,
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> BigCombinationsIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    pub(crate) fn new(items: &'a I, k: usize) -> Self {
+        let n = items.num_elements();
+        let end = BigUint::from(1u32) << n;
+
+        BigCombinationsIterator {
+            items,
+            // The smallest mask with k bits set, or nothing at all if k is too large
+            subset: if k <= n {
+                (BigUint::from(1u32) << k) - 1u32
+            } else {
+                end.clone()
+            },
+            end,
+        }
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> Iterator for BigCombinationsIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    type Item = BigSubset<'a, I>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.subset >= self.end {
+            return None;
+        }
+
+        let subset = self.subset.clone();
+
+        match subset.trailing_zeros() {
+            // There is only one subset with no elements
+            None => self.subset = self.end.clone(),
+            // Gosper's hack, dividing by the lowest set bit is a shift by its position
+            Some(lowest) => {
+                let ripple = &subset + (BigUint::from(1u32) << lowest);
+                self.subset = (((&ripple ^ &subset) >> 2u32) >> lowest) | ripple;
+            }
+        }
+
+        Some(BigSubset::new(self.items, subset))
+    }
+}
+
+/// The subset that is the element of the [`BigPowersetIterator`](BigPowersetIterator) and the
+/// [`BigCombinationsIterator`](BigCombinationsIterator)
+pub struct BigSubset<'a, I: Index<usize> + ?Sized> {
+    items: &'a I,
+    subset: BigUint,
+    next: u64,
+}
+
+impl<'a, I: Index<usize> + ?Sized> BigSubset<'a, I> {
+    fn new(items: &'a I, subset: BigUint) -> Self {
+        BigSubset {
+            items,
+            subset,
+            next: 0,
+        }
+    }
+
+    /// The number of elements in the subset, regardless of how many have been iterated over.
+    pub fn cardinality(&self) -> usize {
+        self.subset.count_ones() as usize
+    }
+
+    /// The bitmask backing the subset, where bit `i` is set if element `i` of the container is
+    /// part of the subset.
+    pub fn bitmask(&self) -> &BigUint {
+        &self.subset
+    }
+}
+
+impl<'a, I: Index<usize> + ?Sized> Iterator for BigSubset<'a, I>
+where
+    I::Output: Sized,
+{
+    type Item = &'a I::Output;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.subset.bits() {
+            self.next += 1;
+
+            if self.subset.bit(self.next - 1) {
+                return Some(&self.items[self.next as usize - 1]);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use crate::Powerset;
+
+    #[test]
+    fn same_as_small() {
+        let items = vec![1, 2, 3, 4, 5];
+
+        assert!(items
+            .powerset()
+            .zip(items.powerset_big())
+            .all(|(small, big)| small.to_vec() == big.cloned().collect::<Vec<i32>>()));
+        assert_eq!(32, items.powerset_big().count());
+
+        for k in 0..=6 {
+            assert!(items
+                .combinations(k)
+                .zip(items.combinations_big(k))
+                .all(|(small, big)| small.to_vec() == big.cloned().collect::<Vec<i32>>()));
+            assert_eq!(
+                items.combinations(k).count(),
+                items.combinations_big(k).count()
+            );
+        }
+    }
+
+    #[test]
+    fn beyond_usize() {
+        let items = (0..70).collect::<Vec<usize>>();
+
+        let mut count = 0;
+        for pair in items.combinations_big(2) {
+            assert_eq!(2, pair.cardinality());
+
+            let pair = pair.cloned().collect::<Vec<usize>>();
+            assert_eq!(2, pair.len());
+            assert!(pair[0] < pair[1]);
+            count += 1;
+        }
+        assert_eq!(70 * 69 / 2, count);
+
+        let last = items.combinations_big(2).last().unwrap();
+        assert_eq!(&(BigUint::from(3u32) << 68), last.bitmask());
+        assert_eq!(vec![68, 69], last.cloned().collect::<Vec<usize>>());
+
+        // The first subsets are the same as for a small container
+        let mut powerset = items.powerset_big();
+        assert_eq!(0, powerset.next().unwrap().count());
+        assert_eq!(
+            vec![0],
+            powerset.next().unwrap().cloned().collect::<Vec<usize>>()
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Iteration over the powerset with several options combined.
+use core::iter::FusedIterator;
+use core::ops::Index;
+
+use crate::{Powerset, SizableContainer, Subset, SubsetIterator};
+
+/// Configures which subsets to visit, returned from
+/// [`Powerset::powerset_builder`](crate::Powerset::powerset_builder).
+///
+/// Without any options, [`build`](PowersetBuilder::build) visits the same subsets as
+/// [`Powerset::powerset`](crate::Powerset::powerset). All the options are combined, so e.g.
+/// `skip_empty` together with `max_size(2)` visits the subsets of one or two elements.
+///
+/// ```
+///     use crate::powerset::Powerset;
+///     let items = [1, 2, 3, 4];
+///
+///     let subsets = items.powerset_builder().min_size(2).skip_full().reverse().build();
+///     assert_eq!(10, subsets.count());
+/// ```
+pub struct PowersetBuilder<'a, I: Index<usize> + ?Sized> {
+    items: &'a I,
+    skip_empty: bool,
+    skip_full: bool,
+    min_size: usize,
+    max_size: usize,
+    reverse: bool,
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized + 'a> PowersetBuilder<'a, I>
+where
+    I::Output: Sized,
+{
+    pub(crate) fn new(items: &'a I) -> Self {
+        PowersetBuilder {
+            items,
+            skip_empty: false,
+            skip_full: false,
+            min_size: 0,
+            max_size: usize::MAX,
+            reverse: false,
+        }
+    }
+
+    /// Don't visit the empty subset.
+    pub fn skip_empty(mut self) -> Self {
+        self.skip_empty = true;
+        self
+    }
+
+    /// Don't visit the subset of all the elements.
+    pub fn skip_full(mut self) -> Self {
+        self.skip_full = true;
+        self
+    }
+
+    /// Only visit subsets with at least `k` elements.
+    pub fn min_size(mut self, k: usize) -> Self {
+        self.min_size = k;
+        self
+    }
+
+    /// Only visit subsets with at most `k` elements.
+    pub fn max_size(mut self, k: usize) -> Self {
+        self.max_size = k;
+        self
+    }
+
+    /// Visit the subsets in decreasing bitmask order, starting with the full set.
+    pub fn reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    /// The iterator over the subsets allowed by all the options.
+    ///
+    /// Panics, like [`Powerset::powerset`](crate::Powerset::powerset), if the container is
+    /// too large.
+    pub fn build(self) -> BuiltPowersetIterator<'a, I> {
+        let num_elements = self.items.num_elements();
+
+        // The empty and the full subsets are the only ones of their sizes, so skipping them
+        // narrows the sizes
+        let min_size = self.min_size.max(usize::from(self.skip_empty));
+        let max_size = if self.skip_full {
+            match num_elements.checked_sub(1) {
+                Some(below_full) => self.max_size.min(below_full),
+                // The empty subset is the full subset, and is skipped
+                None => return BuiltPowersetIterator::empty(self.items),
+            }
+        } else {
+            self.max_size
+        };
+
+        BuiltPowersetIterator {
+            subsets: self.items.powerset(),
+            min_size,
+            max_size,
+            reverse: self.reverse,
+        }
+    }
+}
+
+/// The iterator returned from [`PowersetBuilder::build`](PowersetBuilder::build)
+///
+/// Subsets of the wrong size are jumped over rather than visited one by one, so e.g. the
+/// subsets of at most one element take `O(n)` steps each, not `O(2^n)` in total.
+pub struct BuiltPowersetIterator<'a, I: Index<usize> + ?Sized>
+where
+    I::Output: Sized,
+{
+    subsets: SubsetIterator<'a, I>,
+    min_size: usize,
+    max_size: usize,
+    reverse: bool,
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized + 'a> BuiltPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    fn empty(items: &'a I) -> Self {
+        let mut subsets = items.powerset();
+        subsets.by_ref().for_each(drop);
+
+        BuiltPowersetIterator {
+            subsets,
+            min_size: 0,
+            max_size: usize::MAX,
+            reverse: false,
+        }
+    }
+
+    // Move the front of the subsets up to the first one of an allowed size
+    fn skip_front(&mut self) {
+        let num_elements = self.subsets.items.num_elements();
+        let subsets = &mut self.subsets;
+        match next_sized(subsets.subset, num_elements, self.min_size, self.max_size) {
+            Some(mask) if mask < subsets.end => subsets.subset = mask,
+            _ => subsets.subset = subsets.end,
+        }
+    }
+
+    // Move the back of the subsets down to the last one of an allowed size
+    fn skip_back(&mut self) {
+        let num_elements = self.subsets.items.num_elements();
+        let subsets = &mut self.subsets;
+        if subsets.subset >= subsets.end {
+            return;
+        }
+
+        match prev_sized(subsets.end - 1, num_elements, self.min_size, self.max_size) {
+            Some(mask) if mask >= subsets.subset => subsets.end = mask + 1,
+            _ => subsets.end = subsets.subset,
+        }
+    }
+}
+
+// The smallest mask from `mask` onwards, of a subset of `n` elements, with between `min` and
+// `max` elements
+fn next_sized(mut mask: usize, n: usize, min: usize, max: usize) -> Option<usize> {
+    let max = max.min(n);
+    if min > max {
+        return None;
+    }
+
+    loop {
+        if mask >= 1 << n {
+            return None;
+        }
+
+        let ones = mask.count_ones() as usize;
+        if ones > max {
+            // Every mask up to the lowest set bit adds elements, so carry it upwards instead
+            mask += mask & mask.wrapping_neg();
+        } else {
+            // Fill the lowest unset bits, which there are enough of as `min <= n`
+            for _ in ones..min {
+                mask |= mask + 1;
+            }
+            return Some(mask);
+        }
+    }
+}
+
+// The largest mask up to `mask`, of a subset of `n` elements, with between `min` and `max`
+// elements
+fn prev_sized(mask: usize, n: usize, min: usize, max: usize) -> Option<usize> {
+    // Complementing reverses the order of the masks, and turns sizes `k` into `n - k`
+    let full = (1 << n) - 1;
+    let complement_min = n - max.min(n);
+    let complement_max = n.checked_sub(min)?;
+    next_sized(full ^ mask, n, complement_min, complement_max).map(|mask| full ^ mask)
+}
+
+// Only a reference to the container is stored, so `I` itself doesn't need to be `Clone`
+impl<'a, I: Index<usize> + ?Sized> Clone for BuiltPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    fn clone(&self) -> Self {
+        BuiltPowersetIterator {
+            subsets: self.subsets.clone(),
+            min_size: self.min_size,
+            max_size: self.max_size,
+            reverse: self.reverse,
+        }
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized + 'a> Iterator for BuiltPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    type Item = Subset<'a, I>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reverse {
+            self.skip_back();
+            self.subsets.next_back()
+        } else {
+            self.skip_front();
+            self.subsets.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.subsets.size_hint().1)
+    }
+}
+
+/// Iterate over the subsets that are left without consuming the iterator, by iterating over
+/// a clone of it, so the same configured powerset can be visited several times.
+impl<'a, I: Index<usize> + SizableContainer + ?Sized + 'a> IntoIterator
+    for &BuiltPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    type Item = Subset<'a, I>;
+    type IntoIter = BuiltPowersetIterator<'a, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.clone()
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized + 'a> DoubleEndedIterator
+    for BuiltPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.reverse {
+            self.skip_front();
+            self.subsets.next()
+        } else {
+            self.skip_back();
+            self.subsets.next_back()
+        }
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized + 'a> FusedIterator
+    for BuiltPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Powerset;
+
+    fn masks<'a>(subsets: impl Iterator<Item = crate::Subset<'a, [i32; 4]>>) -> Vec<usize> {
+        subsets.map(|subset| subset.bitmask()).collect()
+    }
+
+    #[test]
+    fn no_options() {
+        let items = [1, 2, 3, 4];
+        assert_eq!(
+            masks(items.powerset()),
+            masks(items.powerset_builder().build())
+        );
+    }
+
+    #[test]
+    fn combined_options() {
+        let items = [1, 2, 3, 4];
+
+        let small = items.powerset_builder().skip_empty().max_size(2).build();
+        assert_eq!(
+            vec![0b0001, 0b0010, 0b0011, 0b0100, 0b0101, 0b0110, 0b1000, 0b1001, 0b1010, 0b1100],
+            masks(small)
+        );
+
+        let large = items
+            .powerset_builder()
+            .skip_empty()
+            .min_size(3)
+            .reverse()
+            .build();
+        assert_eq!(vec![0b1111, 0b1110, 0b1101, 0b1011, 0b0111], masks(large));
+
+        let proper = items.powerset_builder().skip_full().skip_empty().build();
+        assert_eq!(14, proper.count());
+
+        let none = items.powerset_builder().min_size(3).max_size(2).build();
+        assert_eq!(0, none.count());
+    }
+
+    #[test]
+    fn double_ended() {
+        let items = [1, 2, 3, 4];
+        let mut subsets = items.powerset_builder().min_size(2).reverse().build();
+        assert_eq!(0b1111, subsets.next().unwrap().bitmask());
+        assert_eq!(0b0011, subsets.next_back().unwrap().bitmask());
+        assert_eq!(9, subsets.count());
+    }
+
+    #[test]
+    fn iterate_by_reference() {
+        let items = [1, 2, 3, 4];
+        let subsets = items.powerset_builder().skip_empty().max_size(1).build();
+
+        let mut first = Vec::new();
+        for subset in &subsets {
+            first.push(subset.bitmask());
+        }
+        let second = masks((&subsets).into_iter());
+        assert_eq!(vec![0b0001, 0b0010, 0b0100, 0b1000], first);
+        assert_eq!(first, second);
+
+        let mut by_value = Vec::new();
+        for subset in subsets {
+            by_value.push(subset.bitmask());
+        }
+        assert_eq!(first, by_value);
+    }
+
+    #[test]
+    fn same_as_filtering() {
+        let items = [1, 2, 3, 4, 5, 6];
+        for min in 0..=7 {
+            for max in 0..=7 {
+                let expected = items
+                    .powerset()
+                    .filter(|subset| (min..=max).contains(&subset.cardinality()))
+                    .map(|subset| subset.bitmask())
+                    .collect::<Vec<_>>();
+                let builder = || items.powerset_builder().min_size(min).max_size(max);
+
+                let forwards = builder().build().map(|subset| subset.bitmask());
+                assert_eq!(expected, forwards.collect::<Vec<_>>());
+                let backwards = builder().reverse().build().rev();
+                assert_eq!(expected, backwards.map(|s| s.bitmask()).collect::<Vec<_>>());
+
+                // Alternate between the ends, which must meet without overlapping
+                let mut subsets = builder().build();
+                let (mut front, mut back) = (Vec::new(), Vec::new());
+                while let Some(subset) = subsets.next() {
+                    front.push(subset.bitmask());
+                    if let Some(subset) = subsets.next_back() {
+                        back.push(subset.bitmask());
+                    }
+                }
+                front.extend(back.into_iter().rev());
+                assert_eq!(expected, front);
+            }
+        }
+    }
+
+    #[test]
+    fn sparse_sizes() {
+        let items = [0; 60];
+
+        let mut singletons = items.powerset_builder().skip_empty().max_size(1).build();
+        assert_eq!(60, singletons.clone().count());
+        assert_eq!(1 << 59, singletons.next_back().unwrap().bitmask());
+
+        let mut large = items.powerset_builder().min_size(59).reverse().build();
+        assert_eq!((1 << 60) - 1, large.next().unwrap().bitmask());
+        assert_eq!((1 << 59) - 1, large.next_back().unwrap().bitmask());
+        assert_eq!(59, large.count());
+    }
+
+    #[test]
+    fn empty_container() {
+        let items: [i32; 0] = [];
+        assert_eq!(1, items.powerset_builder().build().count());
+        assert_eq!(0, items.powerset_builder().skip_empty().build().count());
+        assert_eq!(0, items.powerset_builder().skip_full().build().count());
+    }
+}
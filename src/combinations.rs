@@ -0,0 +1,112 @@
+//! Iteration over the subsets of a fixed size.
+use core::ops::Index;
+
+use crate::{SizableContainer, Subset};
+
+/// The iterator returned from [`Powerset::combinations`](crate::Powerset::combinations).
+///
+/// Only the bitmasks with exactly `k` bits set are visited, moving from one to the next with
+/// Gosper's hack, so no time is spent on subsets of the wrong size.
+pub struct CombinationsIterator<'a, I: Index<usize> + ?Sized>
+where
+    I::Output: Sized,
+{
+    items: &'a I,
+    subset: usize,
+    end: usize,
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> CombinationsIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    pub(crate) fn new(items: &'a I, k: usize) -> Self {
+        let n = items.num_elements();
+        let end = 1 << n;
+
+        CombinationsIterator {
+            items,
+            // The smallest mask with k bits set, or nothing at all if k is too large
+            subset: if k <= n { (1 << k) - 1 } else { end },
+            end,
+        }
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> Iterator for CombinationsIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    type Item = Subset<'a, I>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.subset >= self.end {
+            return None;
+        }
+
+        let subset = self.subset;
+
+        if subset == 0 {
+            // There is only one subset with no elements
+            self.subset = self.end;
+        } else {
+            // Gosper's hack: move the lowest block of ones one step up, and put the rest
+            // of the block back at the bottom
+            let lowest = subset & subset.wrapping_neg();
+            let ripple = subset + lowest;
+            self.subset = (((ripple ^ subset) >> 2) / lowest) | ripple;
+        }
+
+        Some(Subset::new(self.items, subset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Powerset;
+
+    fn binomial(n: usize, k: usize) -> usize {
+        if k > n {
+            return 0;
+        }
+        (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+    }
+
+    #[test]
+    fn combination_counts() {
+        let items = [1, 2, 3, 4, 5, 6];
+
+        for k in 0..=8 {
+            let mut count = 0;
+            for subset in items.combinations(k) {
+                assert_eq!(k, subset.count());
+                count += 1;
+            }
+            assert_eq!(binomial(6, k), count);
+        }
+    }
+
+    #[test]
+    fn combination_order() {
+        let items = [1, 2, 3, 4];
+
+        let pairs = items
+            .combinations(2)
+            .map(|subset| subset.cloned().collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 3],
+                vec![1, 4],
+                vec![2, 4],
+                vec![3, 4]
+            ],
+            pairs
+        );
+
+        let empty = Vec::<i32>::new();
+        assert_eq!(1, empty.combinations(0).count());
+        assert_eq!(0, empty.combinations(1).count());
+    }
+}
@@ -0,0 +1,24 @@
+//! Errors from taking the powerset of a container.
+use core::fmt;
+
+/// The error returned from [`Powerset::try_powerset`](crate::Powerset::try_powerset)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowersetError {
+    /// The container has too many elements to represent its subsets as bitmasks
+    TooLarge,
+}
+
+impl fmt::Display for PowersetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowersetError::TooLarge => write!(
+                f,
+                "the container is too large, at most {} elements are supported",
+                crate::max_supported_elements()
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PowersetError {}
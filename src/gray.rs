@@ -0,0 +1,101 @@
+//! Iteration over the powerset in Gray code order.
+use core::ops::Index;
+
+use crate::{SizableContainer, Subset};
+
+/// The iterator returned from [`Powerset::powerset_gray`](crate::Powerset::powerset_gray).
+///
+/// Along with every subset it yields the index of the element that was added or removed
+/// compared to the previous subset, so running computations over the subsets can be updated
+/// in constant time. The first subset is the empty one, and has no changed index.
+pub struct GrayPowersetIterator<'a, I: Index<usize> + ?Sized>
+where
+    I::Output: Sized,
+{
+    items: &'a I,
+    step: usize,
+    end: usize,
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> GrayPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    pub(crate) fn new(items: &'a I) -> Self {
+        GrayPowersetIterator {
+            items,
+            step: 0,
+            end: 1 << items.num_elements(),
+        }
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> Iterator for GrayPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    type Item = (Subset<'a, I>, Option<usize>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.step >= self.end {
+            return None;
+        }
+
+        let step = self.step;
+        self.step += 1;
+
+        // Going from step - 1 to step flips the lowest set bit of step in the Gray code
+        let changed = if step == 0 {
+            None
+        } else {
+            Some(step.trailing_zeros() as usize)
+        };
+
+        Some((Subset::new(self.items, step ^ (step >> 1)), changed))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.step;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> ExactSizeIterator
+    for GrayPowersetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Powerset;
+
+    #[test]
+    fn gray_order() {
+        let items = [1, 2, 3, 4, 5];
+        let mut seen = [false; 32];
+        let mut previous = None;
+
+        for (subset, changed) in items.powerset_gray() {
+            let mask = subset.bitmask();
+
+            assert!(!seen[mask]);
+            seen[mask] = true;
+
+            match previous {
+                None => {
+                    assert_eq!(0, mask);
+                    assert_eq!(None, changed);
+                }
+                Some(previous) => {
+                    let difference: usize = mask ^ previous;
+                    assert_eq!(1, difference.count_ones());
+                    assert_eq!(Some(difference.trailing_zeros() as usize), changed);
+                }
+            }
+            previous = Some(mask);
+        }
+
+        assert!(seen.iter().all(|&seen| seen));
+    }
+}
@@ -1,11 +1,69 @@
 //! Implements a way to iterate over the [`Powerset`](Powerset) of some type.
 //! Each type needs to have implemented `Index<usize>` and the trait [`SizableContainer`](SizableContainer), which should
 //! in essence return the length of the container.
-use std::ops::Index;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, boxed::Box, collections::VecDeque, vec::Vec};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::{Index, RangeInclusive};
+
+#[cfg(feature = "stream")]
+use futures::stream::Stream;
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+mod adapters;
+#[cfg(feature = "bignum")]
+mod big;
+mod builder;
+mod combinations;
+mod error;
+mod gray;
+mod mask;
+#[cfg(feature = "alloc")]
+mod owned;
+#[cfg(feature = "serde")]
+mod serialize;
+mod small;
+
+pub use adapters::{IndexAdapter, RangeContainer};
+#[cfg(feature = "alloc")]
+pub use adapters::{PowersetContainer, SortedView, ToSortedView};
+#[cfg(feature = "bignum")]
+pub use big::{BigCombinationsIterator, BigPowersetIterator, BigSubset};
+pub use builder::{BuiltPowersetIterator, PowersetBuilder};
+pub use combinations::CombinationsIterator;
+pub use error::PowersetError;
+pub use gray::GrayPowersetIterator;
+pub use mask::MaskStorage;
+#[cfg(feature = "alloc")]
+pub use owned::{IntoPowerset, OwnedPowersetIterator};
+/// Derive [`SizableContainer`](trait@SizableContainer) for a struct, from the `len()` of the
+/// field marked with `#[powerset(len)]`.
+#[cfg(feature = "derive")]
+pub use powerset_derive::SizableContainer;
+#[cfg(feature = "serde")]
+pub use serialize::SubsetMask;
+pub use small::{Const, MaskWidth, SmallPowerset, SmallPowersetIterator};
 
 /// This trait needs to be implemented for the thing you want to have your powerset over.
 /// In the example of a vec, it only needs to return the len of the vec.
 /// In general, it has to return the greatest possible value to be indexed by plus one
+///
+/// Containers that are not indexed by `usize` can be wrapped in an
+/// [`IndexAdapter`](IndexAdapter).
 pub trait SizableContainer {
     fn num_elements(&self) -> usize;
 }
@@ -26,190 +84,3494 @@ pub trait SizableContainer {
 ///     }
 ///
 /// ```
-pub trait Powerset<'a, I: Index<usize> + SizableContainer>
+///
+/// Every subset is represented by a `usize` bitmask, so the container can hold at most
+/// [`max_supported_elements`](max_supported_elements) elements (63 on 64-bit targets).
+/// Calling [`powerset`](Powerset::powerset) on a bigger container panics, rather than
+/// silently producing wrong subsets. Use
+/// [`powerset_with`](Powerset::powerset_with) to pick a wider [`MaskStorage`](MaskStorage).
+pub trait Powerset<'a, I: Index<usize> + SizableContainer + ?Sized + 'a>
 where
     I::Output: Sized,
 {
     fn powerset(&'a self) -> SubsetIterator<'a, I>;
+
+    /// Like [`powerset`](Powerset::powerset), but returns an error instead of panicking if the
+    /// container is too large.
+    fn try_powerset(&'a self) -> Result<SubsetIterator<'a, I>, PowersetError>;
+
+    /// Like [`powerset`](Powerset::powerset), but the subsets are backed by a bitmask of type
+    /// `M` instead of `usize`. With `u128`, containers of up to 127 elements are supported.
+    ///
+    /// ```
+    ///     use crate::powerset::Powerset;
+    ///     let items = (0..100).collect::<Vec<i32>>();
+    ///
+    ///     let full = items.powerset_with::<u128>().next_back().unwrap();
+    ///     assert_eq!(100, full.count());
+    /// ```
+    fn powerset_with<M: MaskStorage>(&'a self) -> SubsetIterator<'a, I, M>;
+
+    /// Configure which subsets to visit, e.g. only the non-empty subsets of at most two
+    /// elements, in reverse order.
+    fn powerset_builder(&'a self) -> PowersetBuilder<'a, I>;
+
+    /// Check if the container is small enough for [`powerset`](Powerset::powerset), so it
+    /// doesn't panic.
+    fn fits(&self) -> bool;
+
+    /// The number of subsets of the container, i.e. `2^n` for `n` elements.
+    ///
+    /// Panics, like [`powerset`](Powerset::powerset), if the container is too large.
+    fn num_subsets(&self) -> usize;
+
+    /// Like [`powerset`](Powerset::powerset), but the empty subset is skipped.
+    fn nonempty_powerset(&'a self) -> SubsetIterator<'a, I>;
+
+    /// Like [`powerset`](Powerset::powerset), but the full set is skipped, so only the proper
+    /// subsets are visited.
+    fn proper_powerset(&'a self) -> SubsetIterator<'a, I>;
+
+    /// Like [`powerset`](Powerset::powerset), but the iteration starts at the subset with
+    /// bitmask `start`. This can be used to resume an earlier iteration, by saving the
+    /// [`bitmask`](Subset::bitmask) of the next subset.
+    ///
+    /// Panics if `start` is greater than the number of subsets.
+    fn powerset_from(&'a self, start: usize) -> SubsetIterator<'a, I>;
+
+    /// Iterate over all subsets in binary-reflected Gray code order, where each subset differs
+    /// from the previous one by a single element.
+    fn powerset_gray(&'a self) -> GrayPowersetIterator<'a, I>;
+
+    /// Split the powerset into `n` iterators over contiguous ranges of subsets, with sizes
+    /// differing by at most one. Iterating over the chunks in order visits the same subsets as
+    /// [`powerset`](Powerset::powerset), so they can be handed out to different threads.
+    ///
+    /// Panics if `n` is zero.
+    #[cfg(feature = "alloc")]
+    fn powerset_chunks(&'a self, n: usize) -> Vec<SubsetIterator<'a, I>>;
+
+    /// Call `f` with the elements of every subset, in the order of
+    /// [`powerset`](Powerset::powerset). The elements are cloned into a single buffer that is
+    /// reused for every subset, so only one allocation is made.
+    #[cfg(feature = "alloc")]
+    fn for_each_into<F: FnMut(&[I::Output])>(&self, f: F)
+    where
+        I::Output: Clone;
+
+    /// Distribute all the subsets into `m` buckets, where a subset goes into the bucket given
+    /// by its bitmask modulo `m`. The subsets in every bucket are in increasing bitmask order.
+    ///
+    /// Panics if `m` is zero.
+    #[cfg(feature = "alloc")]
+    fn bucket_powerset(&'a self, m: usize) -> Vec<Vec<Subset<'a, I>>>;
+
+    /// Collect the elements of every subset into a `Vec`, in the order of
+    /// [`powerset`](Powerset::powerset).
+    #[cfg(feature = "alloc")]
+    fn powerset_vecs(&self) -> Vec<Vec<I::Output>>
+    where
+        I::Output: Clone;
+
+    /// Collect all the subsets into a container, so the powerset of the powerset can be taken.
+    #[cfg(feature = "alloc")]
+    fn powerset_container(&'a self) -> PowersetContainer<'a, I>;
+
+    /// Get the subset given by a bitmask, where bit `i` selects element `i` of the container.
+    /// Returns `None` if the mask selects elements beyond the end of the container, or if the
+    /// container is too large for [`powerset`](Powerset::powerset).
+    fn subset_at(&'a self, mask: usize) -> Option<Subset<'a, I>>;
+
+    /// Get the subset selecting element `i` of the container if `mask[i]` is `true`. This is
+    /// the inverse of [`Subset::to_bool_mask`](Subset::to_bool_mask).
+    ///
+    /// Panics if the mask doesn't have one entry per element of the container.
+    fn subset_from_bool_mask(&'a self, mask: &[bool]) -> Subset<'a, I>;
+
+    /// Iterate over the subsets with exactly `k` elements, in increasing bitmask order.
+    fn combinations(&'a self, k: usize) -> CombinationsIterator<'a, I>;
+
+    /// The number of subsets with exactly `k` elements, i.e. the binomial coefficient
+    /// `C(n, k)` for `n` elements. This is the number of subsets
+    /// [`combinations`](Powerset::combinations) visits.
+    ///
+    /// Panics if the number doesn't fit in a `u128`.
+    fn num_combinations(&self, k: usize) -> u128;
+
+    /// Iterate over all pairs of different elements, in the order of
+    /// [`combinations(2)`](Powerset::combinations). The elements of a pair are in the order
+    /// they appear in the container.
+    fn pairs(&'a self) -> impl Iterator<Item = (&'a I::Output, &'a I::Output)>;
+
+    /// Iterate over all triples of different elements, in the order of
+    /// [`combinations(3)`](Powerset::combinations). The elements of a triple are in the order
+    /// they appear in the container.
+    fn triples(&'a self) -> impl Iterator<Item = (&'a I::Output, &'a I::Output, &'a I::Output)>;
+
+    /// Iterate over the subsets with a number of elements in `sizes`. The subsets are ordered
+    /// by size, and only the subsets of the right sizes are visited.
+    fn powerset_sized(
+        &'a self,
+        sizes: RangeInclusive<usize>,
+    ) -> impl Iterator<Item = Subset<'a, I>>;
+
+    /// Iterate over all subsets ordered by size: first the empty subset, then all subsets with
+    /// one element, then all subsets with two elements, and so on.
+    fn powerset_by_size(&'a self) -> impl Iterator<Item = Subset<'a, I>>;
+
+    /// Iterate over the subsets with at most `max_size` elements, ordered by size. Only the
+    /// subsets of the right sizes are visited, so this is cheap for a small `max_size` even
+    /// when the container is large.
+    fn powerset_up_to(&'a self, max_size: usize) -> impl Iterator<Item = Subset<'a, I>>;
+
+    /// Iterate over all subsets in lexicographic order of their indices, i.e. `[]`, `[0]`,
+    /// `[0, 1]`, `[0, 1, 2]`, `[0, 2]`, `[1]`, `[1, 2]`, `[2]` for three elements.
+    fn powerset_lex(&'a self) -> impl Iterator<Item = Subset<'a, I>>;
+
+    /// Iterate over all subsets in reverse order, starting with the full set and ending with
+    /// the empty set.
+    fn powerset_rev(&'a self) -> impl Iterator<Item = Subset<'a, I>>;
+
+    /// Iterate over all subsets together with their bitmask, which is also their position in
+    /// the iteration of [`powerset`](Powerset::powerset).
+    fn powerset_enumerated(&'a self) -> impl Iterator<Item = (usize, Subset<'a, I>)>;
+
+    /// The subset with the largest `key`, out of all subsets. If several subsets have the
+    /// largest key, the last one in the order of [`powerset`](Powerset::powerset) is
+    /// returned.
+    ///
+    /// There is always at least the empty subset, so this never returns `None`.
+    fn best_subset<K: Ord, F: FnMut(&Subset<'a, I>) -> K>(
+        &'a self,
+        key: F,
+    ) -> Option<Subset<'a, I>>;
+
+    /// Iterate over the `2^(n-1)` subsets that contain the element at `index`, in increasing
+    /// bitmask order.
+    ///
+    /// Panics if `index` is beyond the end of the container.
+    fn powerset_containing(&'a self, index: usize) -> impl Iterator<Item = Subset<'a, I>>;
+
+    /// Iterate over the subsets that contain all the elements at the indices in `forced_in`,
+    /// and none of the elements at the indices in `forced_out`, in increasing bitmask order.
+    ///
+    /// Panics if an index is beyond the end of the container, or is both forced in and out.
+    fn constrained_powerset(
+        &'a self,
+        forced_in: &[usize],
+        forced_out: &[usize],
+    ) -> impl Iterator<Item = Subset<'a, I>>;
+
+    /// Iterate over all ordered pairs `(a, b)` of subsets that have no elements in common.
+    /// Every element is either in `a`, in `b`, or in neither, so there are `3^n` pairs.
+    fn disjoint_pairs(&'a self) -> impl Iterator<Item = (Subset<'a, I>, Subset<'a, I>)>;
+
+    /// Iterate over the pairs of a subset and its complement, with every unordered pair
+    /// visited once. The first subset never contains the last element, so there are
+    /// `2^(n-1)` pairs, starting with the empty and the full subset. An empty container has
+    /// the single pair of two empty subsets.
+    fn complement_pairs(&'a self) -> impl Iterator<Item = (Subset<'a, I>, Subset<'a, I>)>;
+
+    /// Iterate over all pairs of a subset of this container and a subset of `other`. For
+    /// every subset of this container, all the subsets of `other` are visited in order.
+    fn powerset_product<'b, J: Index<usize> + SizableContainer + ?Sized>(
+        &'a self,
+        other: &'b J,
+    ) -> impl Iterator<Item = (Subset<'a, I>, Subset<'b, J>)>
+    where
+        J::Output: Sized;
+
+    /// Write every subset to `w` as it is visited, one per line, with the elements of a subset
+    /// separated by `sep`. The empty subset is written as an empty line.
+    ///
+    /// Nothing is buffered, so wrap `w` in a `BufWriter` when writing to a file or socket.
+    #[cfg(feature = "std")]
+    fn write_powerset<W: Write>(&'a self, w: &mut W, sep: &str) -> io::Result<()>
+    where
+        I::Output: fmt::Display;
+
+    /// Pick a subset uniformly at random, i.e. every element is included with probability 1/2.
+    #[cfg(feature = "rand")]
+    fn random_subset<R: Rng>(&'a self, rng: &mut R) -> Subset<'a, I>;
+
+    /// Pick a random subset, where element `i` is included with probability `probs[i]`,
+    /// independently of the other elements.
+    ///
+    /// Panics if there isn't one probability per element, or a probability is outside of
+    /// `0.0..=1.0`.
+    #[cfg(feature = "rand")]
+    fn random_subset_weighted<R: Rng>(&'a self, probs: &[f64], rng: &mut R) -> Subset<'a, I>;
+
+    /// Iterate over all subsets in a pseudo-random order given by `seed`. Every subset is
+    /// visited exactly once, and the same seed always gives the same order.
+    ///
+    /// The order comes from a cheap permutation of the bitmasks, so it is good enough to
+    /// avoid systematic biases from the usual order, but it is not cryptographically random.
+    #[cfg(feature = "rand")]
+    fn powerset_shuffled(&'a self, seed: u64) -> impl Iterator<Item = Subset<'a, I>>;
+
+    /// Pick `k` different subsets uniformly at random, in a random order. If `k` is at least
+    /// the number of subsets, all of them are returned.
+    #[cfg(all(feature = "rand", feature = "alloc"))]
+    fn sample_subsets<R: Rng>(&'a self, k: usize, rng: &mut R) -> Vec<Subset<'a, I>>;
+
+    /// Like [`powerset`](Powerset::powerset), but the subsets are backed by a `BigUint`, so
+    /// there is no limit on the number of elements.
+    #[cfg(feature = "bignum")]
+    fn powerset_big(&'a self) -> BigPowersetIterator<'a, I>;
+
+    /// Like [`combinations`](Powerset::combinations), but the subsets are backed by a
+    /// `BigUint`, so there is no limit on the number of elements. With a small `k` this works
+    /// well even for huge containers.
+    #[cfg(feature = "bignum")]
+    fn combinations_big(&'a self, k: usize) -> BigCombinationsIterator<'a, I>;
+
+    /// Iterate over all subsets in parallel with rayon, splitting the range of bitmasks across
+    /// threads. The subsets are shared between threads, so the container has to be `Sync`.
+    #[cfg(feature = "rayon")]
+    fn par_powerset(&'a self) -> impl IndexedParallelIterator<Item = Subset<'a, I>>
+    where
+        I: Sync;
+
+    /// Fold over all subsets in parallel, without collecting them. Every thread folds its
+    /// share of the subsets with `fold`, starting from a clone of `identity`, and the results
+    /// are combined with `reduce`. `identity` has to be the identity of `reduce`, as it may be
+    /// used any number of times.
+    ///
+    /// The container is shared between the threads, so it has to be `Sync`, and the partial
+    /// results are sent between threads, so `T` has to be `Send`.
+    #[cfg(feature = "rayon")]
+    fn par_fold<T, F, R>(&'a self, identity: T, fold: F, reduce: R) -> T
+    where
+        I: Sync,
+        T: Clone + Send + Sync,
+        F: Fn(T, Subset<'a, I>) -> T + Sync,
+        R: Fn(T, T) -> T + Sync;
+
+    /// Like [`powerset`](Powerset::powerset), but as an asynchronous stream. The subsets are
+    /// computed on demand without ever waiting, so this is only useful to feed the subsets
+    /// into an async pipeline.
+    #[cfg(feature = "stream")]
+    fn powerset_stream(&'a self) -> impl Stream<Item = Subset<'a, I>>;
 }
 
 /// The iterator returned from the [`Powerset`](Powerset) trait
-pub struct SubsetIterator<'a, I: Index<usize>>
+pub struct SubsetIterator<'a, I: Index<usize> + ?Sized, M: MaskStorage = usize>
 where
     I::Output: Sized,
 {
     items: &'a I,
-    subset: u64,
+    subset: M,
+    end: M,
+    // The range of masks the iterator was created with, to measure `progress` against
+    bounds: (M, M),
 }
 
-impl<'a, I: Index<usize> + SizableContainer> Iterator for SubsetIterator<'a, I>
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> SubsetIterator<'a, I, M>
 where
     I::Output: Sized,
 {
-    type Item = Subset<'a, I>;
-    fn next(&mut self) -> Option<Self::Item> {
-        // Check before increment, to avoid unbounded incrementation
-        if self.subset >= 1 << self.items.num_elements() {
-            return None;
+    // Restrict the iterator to the masks in `start..end`
+    fn with_bounds(self, start: M, end: M) -> Self {
+        SubsetIterator {
+            subset: start,
+            end,
+            bounds: (start, end),
+            ..self
         }
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized> SubsetIterator<'a, I>
+where
+    I::Output: Sized,
+{
+    /// The number of subsets that are left, without consuming the iterator.
+    pub fn remaining(&self) -> usize {
+        self.end - self.subset
+    }
 
-        self.subset += 1;
+    /// The fraction of the subsets of the iterator that have been visited, or skipped over,
+    /// from `0.0` before the first subset to `1.0` when the iterator is done. This is relative
+    /// to the subsets the iterator covers, so e.g. each of the [`powerset_chunks`](Powerset::powerset_chunks)
+    /// goes from `0.0` to `1.0` on its own.
+    pub fn progress(&self) -> f64 {
+        let (start, end) = self.bounds;
+        if start == end {
+            return 1.0;
+        }
 
-        Some(Subset {
-            items: self.items,
-            subset: self.subset - 1,
-            next: 0,
-        })
+        1.0 - self.remaining() as f64 / (end - start) as f64
+    }
+
+    /// Jump to the subset with bitmask `mask`, so it is the next one to be visited. Unlike
+    /// [`nth`](Iterator::nth) this is absolute, and can go back to subsets that were already
+    /// visited.
+    ///
+    /// Panics if `mask` is outside the subsets the iterator covers, which for a bounded
+    /// iterator like one of the [`powerset_chunks`](Powerset::powerset_chunks) is less than the whole powerset.
+    /// Subsets taken from the back with [`next_back`](DoubleEndedIterator::next_back) can't be
+    /// sought to again.
+    pub fn seek(&mut self, mask: usize) {
+        let start = self.bounds.0;
+        assert!(
+            start <= mask && mask < self.end,
+            "cannot seek to subset {}, the iterator covers subsets {}..{}",
+            mask,
+            start,
+            self.end
+        );
+
+        self.subset = mask;
+    }
+
+    /// Call `f` on every subset that is left, stopping at the first error and returning it.
+    /// This is [`try_for_each`](Iterator::try_for_each) specialized to `Result`s.
+    pub fn try_for_each_subset<E, F: FnMut(Subset<'a, I>) -> Result<(), E>>(
+        mut self,
+        f: F,
+    ) -> Result<(), E> {
+        self.try_for_each(f)
     }
 }
 
-/// The subset that is the element of the [`SubsetIterator`](SubsetIterator)
-pub struct Subset<'a, I: Index<usize>> {
-    items: &'a I,
-    subset: u64,
-    next: usize,
+// Only a reference to the container is stored, so `I` itself doesn't need to be `Clone`
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> Clone for SubsetIterator<'a, I, M>
+where
+    I::Output: Sized,
+{
+    fn clone(&self) -> Self {
+        SubsetIterator {
+            items: self.items,
+            subset: self.subset,
+            end: self.end,
+            bounds: self.bounds,
+        }
+    }
 }
 
-impl<'a, I: Index<usize>> Iterator for Subset<'a, I>
+impl<'a, I: Index<usize> + SizableContainer + ?Sized, M: MaskStorage> Iterator
+    for SubsetIterator<'a, I, M>
 where
     I::Output: Sized,
 {
-    type Item = &'a I::Output;
+    type Item = Subset<'a, I, M>;
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            // Check before increment, to avoid unbounded incrementation
-            if 1 << self.next > self.subset {
-                return None;
-            }
+        // Check before increment, to avoid unbounded incrementation
+        if self.subset >= self.end {
+            return None;
+        }
+
+        self.subset = self.subset + M::ONE;
+
+        Some(Subset::new(self.items, self.subset - M::ONE))
+    }
 
-            self.next += 1;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Wide masks can have more subsets left than fits in a usize
+        match (self.end - self.subset).to_usize() {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
 
-            if 1 << (self.next - 1) & self.subset != 0 {
-                // return the item
-                return Some(&self.items[self.next - 1]);
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Every subset is identified by its bitmask, so we can jump straight to it
+        match M::from_usize(n) {
+            Some(n) if n < self.end - self.subset => {
+                self.subset = self.subset + n;
+                self.next()
+            }
+            _ => {
+                self.subset = self.end;
+                None
             }
         }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let max_size = (self.subset >> self.next).count_ones() as usize;
-        (max_size, Some(max_size))
+    fn last(mut self) -> Option<Self::Item> {
+        // The last subset is the one just before the end, no need to visit the others
+        self.next_back()
+    }
+
+    fn count(self) -> usize {
+        (self.end - self.subset)
+            .to_usize()
+            .expect("there are too many subsets to count in a usize")
     }
 }
 
-impl<'a, I: Index<usize> + SizableContainer> Powerset<'a, I> for I
+/// Shows the number of elements of the container and the bitmask of the next subset, without
+/// the elements themselves.
+impl<'a, I: Index<usize> + SizableContainer + ?Sized, M: MaskStorage> fmt::Debug
+    for SubsetIterator<'a, I, M>
 where
     I::Output: Sized,
 {
-    fn powerset(&'a self) -> SubsetIterator<'a, I> {
-        SubsetIterator {
-            items: self,
-            subset: 0,
-        }
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubsetIterator")
+            .field("num_elements", &self.items.num_elements())
+            .field("subset", &format_args!("{:#b}", self.subset))
+            .field("end", &format_args!("{:#b}", self.end))
+            .finish()
     }
 }
 
-impl<T> SizableContainer for Vec<T> {
-    fn num_elements(&self) -> usize {
-        self.len()
+/// Iterate over the subsets that are left without consuming the iterator, by iterating over
+/// a clone of it. Like any iterator, `SubsetIterator` itself is `IntoIterator` by value.
+impl<'a, I: Index<usize> + SizableContainer + ?Sized, M: MaskStorage> IntoIterator
+    for &SubsetIterator<'a, I, M>
+where
+    I::Output: Sized,
+{
+    type Item = Subset<'a, I, M>;
+    type IntoIter = SubsetIterator<'a, I, M>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.clone()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::Powerset;
+impl<'a, I: Index<usize> + SizableContainer + ?Sized, M: MaskStorage> DoubleEndedIterator
+    for SubsetIterator<'a, I, M>
+where
+    I::Output: Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Check before decrement, so we never pass the front of the iterator
+        if self.subset >= self.end {
+            return None;
+        }
 
-    #[test]
-    fn correct_subsets() {
-        let items = vec![1, 2, 3, 4];
-        let mut powerset = items.powerset();
+        self.end = self.end - M::ONE;
 
-        // Test that we get all subsets of the vector, and nothing more
-        assert_eq!(
-            Vec::<i32>::new(),
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![1],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![2],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![1, 2],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![3],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![1, 3],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![2, 3],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![1, 2, 3],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![4],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![1, 4],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![2, 4],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![1, 2, 4],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![3, 4],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![1, 3, 4],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![2, 3, 4],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert_eq!(
-            vec![1, 2, 3, 4],
-            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
-        );
-        assert!(powerset.next().is_none());
+        Some(Subset::new(self.items, self.end))
     }
+}
 
-    #[test]
-    fn size_hint() {
-        let items = vec![1, 2, 3, 4, 5, 6];
-
-        for mut set in items.powerset() {
-            let mut count = 0;
-            let size_hint = set.size_hint();
-            loop {
-                let elem = set.next();
-                if elem.is_none() {
-                    break;
+// Only masks no wider than a `usize` are guaranteed to count the remaining subsets exactly
+// in a `usize`
+macro_rules! impl_exact_size {
+    ($($mask:ty),*) => {
+        $(
+            impl<'a, I: Index<usize> + SizableContainer + ?Sized> ExactSizeIterator
+                for SubsetIterator<'a, I, $mask>
+            where
+                I::Output: Sized,
+            {
+                fn len(&self) -> usize {
+                    (self.end - self.subset) as usize
                 }
-                count += 1;
-                assert_eq!(size_hint.0 - count, set.size_hint().0);
             }
+        )*
+    };
+}
 
-            assert_eq!(count, size_hint.0);
-            assert_eq!(Some(count), size_hint.1);
+impl_exact_size!(u8, u16, usize);
+#[cfg(not(target_pointer_width = "16"))]
+impl_exact_size!(u32);
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized, M: MaskStorage> FusedIterator
+    for SubsetIterator<'a, I, M>
+where
+    I::Output: Sized,
+{
+}
+
+/// The subset that is the element of the [`SubsetIterator`](SubsetIterator)
+pub struct Subset<'a, I: Index<usize> + ?Sized, M: MaskStorage = usize> {
+    items: &'a I,
+    subset: M,
+    next: usize,
+    back: usize,
+}
+
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> Subset<'a, I, M> {
+    pub(crate) fn new(items: &'a I, subset: M) -> Self {
+        Subset {
+            items,
+            subset,
+            next: 0,
+            // One past the highest element in the subset
+            back: (M::BITS - subset.leading_zeros()) as usize,
+        }
+    }
+
+    /// The number of elements in the subset, regardless of how many have been iterated over.
+    pub fn cardinality(&self) -> usize {
+        self.subset.count_ones() as usize
+    }
+
+    /// Check if this is the empty subset, regardless of how many elements have been iterated
+    /// over.
+    pub fn is_empty(&self) -> bool {
+        self.subset == M::ZERO
+    }
+
+    /// The bitmask backing the subset, where bit `i` is set if element `i` of the container is
+    /// part of the subset.
+    pub fn bitmask(&self) -> M {
+        self.subset
+    }
+
+    /// The position of the subset in the iteration of [`Powerset::powerset`](Powerset::powerset),
+    /// starting at zero. Subsets are visited in the order of their bitmasks, so this is the
+    /// same as the [`bitmask`](Subset::bitmask), and [`Powerset::subset_at`](Powerset::subset_at)
+    /// gives back the subset.
+    pub fn rank(&self) -> M {
+        self.subset
+    }
+
+    /// Check if the element at `index` in the container is part of the subset, regardless of
+    /// how many elements have been iterated over.
+    pub fn contains(&self, index: usize) -> bool {
+        self.subset.bit_set(index)
+    }
+
+    /// The indices of the elements in the subset, in ascending order, regardless of how many
+    /// elements have been iterated over.
+    pub fn indices(&self) -> impl Iterator<Item = usize> {
+        let mut mask = self.subset;
+        core::iter::from_fn(move || {
+            if mask == M::ZERO {
+                return None;
+            }
+            let index = mask.trailing_zeros() as usize;
+            // Clear the lowest set bit
+            mask = mask & (mask - M::ONE);
+            Some(index)
+        })
+    }
+
+    /// Iterate over all the subsets of this subset, in decreasing bitmask order, starting with
+    /// this subset itself and ending with the empty subset. Only the submasks are visited, so
+    /// this takes time proportional to their number, not to the size of the container.
+    pub fn submasks(&self) -> impl Iterator<Item = Subset<'a, I, M>> {
+        let items = self.items;
+        submasks(self.subset).map(move |sub| Subset::new(items, sub))
+    }
+
+    /// Check if the elements of the subset, in the order they appear in the container, are
+    /// equal to `expected`. All the elements are compared, regardless of how many have been
+    /// iterated over.
+    pub fn eq_slice(&self, expected: &[I::Output]) -> bool
+    where
+        I::Output: Sized + PartialEq,
+    {
+        self.iter().eq(expected.iter())
+    }
+
+    /// Rewind the subset, so iterating over it starts over from the first element. Elements
+    /// taken from the back are visited again as well.
+    pub fn reset(&mut self) {
+        *self = Subset::new(self.items, self.subset);
+    }
+
+    /// Iterate over all the elements of the subset, regardless of how many have been iterated
+    /// over, without consuming it.
+    pub fn iter(&self) -> impl Iterator<Item = &'a I::Output> + '_
+    where
+        I::Output: Sized,
+    {
+        Subset::new(self.items, self.subset)
+    }
+
+    /// The subset of the elements that are in either of the subsets.
+    ///
+    /// Both subsets have to be over the same container.
+    pub fn union(&self, other: &Subset<'a, I, M>) -> Subset<'a, I, M> {
+        self.combine(other, self.subset | other.subset)
+    }
+
+    /// The subset of the elements that are in both of the subsets.
+    ///
+    /// Both subsets have to be over the same container.
+    pub fn intersection(&self, other: &Subset<'a, I, M>) -> Subset<'a, I, M> {
+        self.combine(other, self.subset & other.subset)
+    }
+
+    /// The subset of the elements that are in this subset, but not in `other`.
+    ///
+    /// Both subsets have to be over the same container.
+    pub fn difference(&self, other: &Subset<'a, I, M>) -> Subset<'a, I, M> {
+        self.combine(other, self.subset & !other.subset)
+    }
+
+    /// The subset of the elements that are in exactly one of the subsets.
+    ///
+    /// Both subsets have to be over the same container.
+    pub fn symmetric_difference(&self, other: &Subset<'a, I, M>) -> Subset<'a, I, M> {
+        self.combine(other, self.subset ^ other.subset)
+    }
+
+    /// The subset of the elements in this subset that satisfy `pred`. All the elements are
+    /// considered, regardless of how many have been iterated over.
+    pub fn filter_elements<F: FnMut(&I::Output) -> bool>(&self, mut pred: F) -> Subset<'a, I, M>
+    where
+        I::Output: Sized,
+    {
+        let kept = self
+            .indices()
+            .filter(|&index| pred(&self.items[index]))
+            .fold(M::ZERO, |mask, index| mask | M::ONE << index);
+        Subset::new(self.items, kept)
+    }
+
+    /// A new subset with the given mask, from a set operation between `self` and `other`
+    fn combine(&self, other: &Subset<'a, I, M>, subset: M) -> Subset<'a, I, M> {
+        debug_assert!(
+            core::ptr::eq(self.items, other.items),
+            "set operations need subsets of the same container"
+        );
+
+        Subset::new(self.items, subset)
+    }
+
+    /// Clone the elements of the subset into a `Vec`, in the order they appear in the container.
+    /// All the elements are included, regardless of how many have been iterated over.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<I::Output>
+    where
+        I::Output: Sized + Clone,
+    {
+        self.collect_elements()
+    }
+
+    /// Clone the elements of the subset into a `SmallVec`, which keeps up to `N` elements
+    /// inline without allocating. All the elements are included, regardless of how many have
+    /// been iterated over.
+    #[cfg(feature = "smallvec")]
+    pub fn to_smallvec<const N: usize>(&self) -> SmallVec<[I::Output; N]>
+    where
+        I::Output: Sized + Clone,
+    {
+        self.collect_elements()
+    }
+
+    /// Convert the elements of the subset to owned values into a `Vec`, in the order they
+    /// appear in the container. Unlike [`to_vec`](Subset::to_vec) this works for containers of
+    /// unsized elements as well, e.g. a `str` becomes a `String`.
+    #[cfg(feature = "alloc")]
+    pub fn to_owned_vec(&self) -> Vec<<I::Output as ToOwned>::Owned>
+    where
+        I::Output: ToOwned,
+    {
+        self.indices()
+            .map(|index| self.items[index].to_owned())
+            .collect()
+    }
+
+    /// Clone the elements of the subset into any collection, in the order they appear in the
+    /// container. All the elements are included, regardless of how many have been iterated
+    /// over.
+    pub fn collect_elements<C: FromIterator<I::Output>>(&self) -> C
+    where
+        I::Output: Sized + Clone,
+    {
+        Subset::new(self.items, self.subset).cloned().collect()
+    }
+
+    /// Sum clones of the elements of the subset. All the elements are included, regardless of
+    /// how many have been iterated over, unlike `Iterator::sum`, and the subset is not
+    /// consumed.
+    pub fn sum_elements<S: core::iter::Sum<I::Output>>(&self) -> S
+    where
+        I::Output: Sized + Clone,
+    {
+        Subset::new(self.items, self.subset).cloned().sum()
+    }
+
+    /// Map the elements of the subset through `f` into a `Vec`, in the order they appear in the
+    /// container. All the elements are included, regardless of how many have been iterated
+    /// over.
+    #[cfg(feature = "alloc")]
+    pub fn map_to_vec<T, F: FnMut(&I::Output) -> T>(&self, f: F) -> Vec<T>
+    where
+        I::Output: Sized,
+    {
+        Subset::new(self.items, self.subset).map(f).collect()
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized, M: MaskStorage> Subset<'a, I, M> {
+    /// The subset of all the elements of the container that are not in this subset.
+    pub fn complement(&self) -> Subset<'a, I, M> {
+        Subset::new(
+            self.items,
+            !self.subset & full_mask::<M>(self.items.num_elements()),
+        )
+    }
+
+    /// Check if the subset contains all the elements of the container, regardless of how many
+    /// elements have been iterated over.
+    pub fn is_full(&self) -> bool {
+        self.subset == full_mask::<M>(self.items.num_elements())
+    }
+
+    /// Iterate over the subsets of the container that contain all the elements of this
+    /// subset, in increasing bitmask order, starting with this subset itself.
+    pub fn supersets(&self) -> impl Iterator<Item = Subset<'a, I, M>> {
+        let (items, subset) = (self.items, self.subset);
+        submasks_ascending(!subset & full_mask::<M>(items.num_elements()))
+            .map(move |free| Subset::new(items, free | subset))
+    }
+
+    /// This subset with the element at `index` added, if it wasn't already there.
+    ///
+    /// Panics if `index` is beyond the end of the container.
+    pub fn with(&self, index: usize) -> Subset<'a, I, M> {
+        self.assert_in_container(index);
+        Subset::new(self.items, self.subset | M::ONE << index)
+    }
+
+    /// This subset with the element at `index` removed, if it was there.
+    ///
+    /// Panics if `index` is beyond the end of the container.
+    pub fn without(&self, index: usize) -> Subset<'a, I, M> {
+        self.assert_in_container(index);
+        Subset::new(self.items, self.subset & !(M::ONE << index))
+    }
+
+    fn assert_in_container(&self, index: usize) {
+        assert!(
+            index < self.items.num_elements(),
+            "index {} is out of range for a container of {} elements",
+            index,
+            self.items.num_elements()
+        );
+        assert!(
+            index < M::BITS as usize,
+            "index {} is out of range for a mask of {} bits",
+            index,
+            M::BITS
+        );
+    }
+
+    /// One entry per element of the container, which is `true` if the element is part of the
+    /// subset.
+    #[cfg(feature = "alloc")]
+    pub fn to_bool_mask(&self) -> Vec<bool> {
+        (0..self.items.num_elements())
+            .map(|i| self.contains(i))
+            .collect()
+    }
+}
+
+// Only a reference to the container is stored, so `I` itself doesn't need to be `Clone`
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> Clone for Subset<'a, I, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// Copying a subset copies how far it has been iterated over as well, so the copy and the
+/// original continue independently from the same element.
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> Copy for Subset<'a, I, M> {}
+
+/// Two subsets are equal if they select the same elements of the same container. The container
+/// is compared by identity, not by value, and how far the subsets have been iterated over
+/// doesn't matter.
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> PartialEq for Subset<'a, I, M> {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.items, other.items) && self.subset == other.subset
+    }
+}
+
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> Eq for Subset<'a, I, M> {}
+
+/// Subsets are ordered by their number of elements, and subsets of the same size by their
+/// bitmask, so sorting the subsets of a container gives the order of
+/// [`Powerset::powerset_by_size`](Powerset::powerset_by_size). Subsets of different containers
+/// are only ordered by the address of the container, to stay consistent with the equality.
+///
+/// As `Subset` is an iterator, `a.cmp(&b)` resolves to `Iterator::cmp`, which compares the
+/// elements instead. Use `Ord::cmp(&a, &b)` or the comparison operators to compare subsets.
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> Ord for Subset<'a, I, M> {
+    // Like `ptr::eq` in the equality, the length of unsized containers is compared as well
+    #[allow(ambiguous_wide_pointer_comparisons)]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.cardinality()
+            .cmp(&other.cardinality())
+            .then_with(|| self.subset.cmp(&other.subset))
+            .then_with(|| (self.items as *const I).cmp(&(other.items as *const I)))
+    }
+}
+
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> PartialOrd for Subset<'a, I, M> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Consistent with the equality, the container is hashed by identity
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> Hash for Subset<'a, I, M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        core::ptr::hash(self.items, state);
+        self.subset.hash(state);
+    }
+}
+
+/// Shows the bitmask and the number of elements of the subset, without the elements themselves.
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> fmt::Debug for Subset<'a, I, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subset")
+            .field("mask", &format_args!("{:#b}", self.subset))
+            .field("cardinality", &self.cardinality())
+            .finish()
+    }
+}
+
+/// Writes the indices of the elements in the subset, e.g. `{0, 2, 3}`
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> fmt::Display for Subset<'a, I, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+
+        let mut remaining = self.subset;
+        let mut first = true;
+        while remaining != M::ZERO {
+            if !first {
+                write!(f, ", ")?;
+            }
+            first = false;
+
+            write!(f, "{}", remaining.trailing_zeros())?;
+            // Clear the lowest set bit
+            remaining = remaining & (remaining - M::ONE);
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> Iterator for Subset<'a, I, M>
+where
+    I::Output: Sized,
+{
+    type Item = &'a I::Output;
+    fn next(&mut self) -> Option<Self::Item> {
+        // Check before shifting, the front cursor is only below the width of the mask while
+        // there are elements left
+        if self.next >= self.back {
+            return None;
+        }
+
+        // Jump straight to the lowest element at or above the front cursor
+        let index = self.next + (self.subset >> self.next).trailing_zeros() as usize;
+        if index >= self.back {
+            self.next = self.back;
+            return None;
+        }
+
+        self.next = index + 1;
+        Some(&self.items[index])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Only the elements between the two cursors are left
+        let below_back = self.subset & full_mask::<M>(self.back);
+        let max_size = if self.next >= M::BITS as usize {
+            0
+        } else {
+            (below_back >> self.next).count_ones() as usize
+        };
+        (max_size, Some(max_size))
+    }
+}
+
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> DoubleEndedIterator for Subset<'a, I, M>
+where
+    I::Output: Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Check before looking, so we never pass the front cursor
+        if self.back <= self.next {
+            return None;
+        }
+
+        // Jump straight to the highest element below the back cursor
+        let below_back = self.subset & full_mask::<M>(self.back);
+        let top = (M::BITS - below_back.leading_zeros()) as usize;
+        if top <= self.next {
+            self.back = self.next;
+            return None;
+        }
+
+        self.back = top - 1;
+        Some(&self.items[self.back])
+    }
+}
+
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> ExactSizeIterator for Subset<'a, I, M> where
+    I::Output: Sized
+{
+}
+
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> FusedIterator for Subset<'a, I, M> where
+    I::Output: Sized
+{
+}
+
+/// Iterate over clones of all the elements in the subset, regardless of how many have been
+/// iterated over, without consuming it.
+impl<'a, I: Index<usize> + ?Sized, M: MaskStorage> IntoIterator for &Subset<'a, I, M>
+where
+    I::Output: Sized + Clone,
+{
+    type Item = I::Output;
+    type IntoIter = core::iter::Cloned<Subset<'a, I, M>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Subset::new(self.items, self.subset).cloned()
+    }
+}
+
+impl<'a, I: Index<usize> + SizableContainer + ?Sized + 'a> Powerset<'a, I> for I
+where
+    I::Output: Sized,
+{
+    fn powerset(&'a self) -> SubsetIterator<'a, I> {
+        self.powerset_with()
+    }
+
+    fn try_powerset(&'a self) -> Result<SubsetIterator<'a, I>, PowersetError> {
+        if !self.fits() {
+            return Err(PowersetError::TooLarge);
+        }
+
+        Ok(self.powerset())
+    }
+
+    fn powerset_with<M: MaskStorage>(&'a self) -> SubsetIterator<'a, I, M> {
+        assert_supported::<M>(self.num_elements());
+
+        let end = M::ONE << self.num_elements();
+        SubsetIterator {
+            items: self,
+            subset: M::ZERO,
+            end,
+            bounds: (M::ZERO, end),
+        }
+    }
+
+    fn powerset_builder(&'a self) -> PowersetBuilder<'a, I> {
+        PowersetBuilder::new(self)
+    }
+
+    fn fits(&self) -> bool {
+        self.num_elements() <= max_supported_elements() as usize
+    }
+
+    fn num_subsets(&self) -> usize {
+        assert_supported::<usize>(self.num_elements());
+
+        1 << self.num_elements()
+    }
+
+    fn nonempty_powerset(&'a self) -> SubsetIterator<'a, I> {
+        // The empty subset is the first one, so just start one step later
+        let powerset = self.powerset();
+        let end = powerset.end;
+        powerset.with_bounds(1, end)
+    }
+
+    fn proper_powerset(&'a self) -> SubsetIterator<'a, I> {
+        // The full set is the last one, so just stop one step earlier
+        let powerset = self.powerset();
+        let end = powerset.end - 1;
+        powerset.with_bounds(0, end)
+    }
+
+    fn powerset_gray(&'a self) -> GrayPowersetIterator<'a, I> {
+        assert_supported::<usize>(self.num_elements());
+
+        GrayPowersetIterator::new(self)
+    }
+
+    fn powerset_from(&'a self, start: usize) -> SubsetIterator<'a, I> {
+        let powerset = self.powerset();
+        assert!(
+            start <= powerset.end,
+            "cannot start at subset {}, there are only {} subsets",
+            start,
+            powerset.end
+        );
+
+        SubsetIterator {
+            subset: start,
+            ..powerset
+        }
+    }
+
+    fn powerset_rev(&'a self) -> impl Iterator<Item = Subset<'a, I>> {
+        self.powerset().rev()
+    }
+
+    fn powerset_enumerated(&'a self) -> impl Iterator<Item = (usize, Subset<'a, I>)> {
+        self.powerset().map(|subset| (subset.bitmask(), subset))
+    }
+
+    fn best_subset<K: Ord, F: FnMut(&Subset<'a, I>) -> K>(
+        &'a self,
+        key: F,
+    ) -> Option<Subset<'a, I>> {
+        self.powerset().max_by_key(key)
+    }
+
+    fn powerset_containing(&'a self, index: usize) -> impl Iterator<Item = Subset<'a, I>> {
+        let num_elements = self.num_elements();
+        assert_supported::<usize>(num_elements);
+        assert!(
+            index < num_elements,
+            "cannot require element {}, there are only {} elements",
+            index,
+            num_elements
+        );
+
+        let required = 1 << index;
+        submasks_ascending(full_mask::<usize>(num_elements) & !required)
+            .map(move |free| Subset::new(self, free | required))
+    }
+
+    fn constrained_powerset(
+        &'a self,
+        forced_in: &[usize],
+        forced_out: &[usize],
+    ) -> impl Iterator<Item = Subset<'a, I>> {
+        let num_elements = self.num_elements();
+        assert_supported::<usize>(num_elements);
+
+        let to_mask = |indices: &[usize]| {
+            indices.iter().fold(0, |mask, &index| {
+                assert!(
+                    index < num_elements,
+                    "cannot constrain element {}, there are only {} elements",
+                    index,
+                    num_elements
+                );
+                mask | 1 << index
+            })
+        };
+        let required = to_mask(forced_in);
+        let excluded = to_mask(forced_out);
+        assert!(
+            required & excluded == 0,
+            "elements cannot be both forced in and forced out"
+        );
+
+        submasks_ascending(full_mask::<usize>(num_elements) & !required & !excluded)
+            .map(move |free| Subset::new(self, free | required))
+    }
+
+    fn disjoint_pairs(&'a self) -> impl Iterator<Item = (Subset<'a, I>, Subset<'a, I>)> {
+        self.powerset().flat_map(move |a| {
+            // The second subset can be any subset of the elements not in the first one
+            submasks(a.complement().bitmask()).map(move |b| (a, Subset::new(self, b)))
+        })
+    }
+
+    fn complement_pairs(&'a self) -> impl Iterator<Item = (Subset<'a, I>, Subset<'a, I>)> {
+        let num_elements = self.num_elements();
+        assert_supported::<usize>(num_elements);
+
+        // Leaving out the last element picks one subset of every pair, but the empty set is
+        // its own complement in an empty container
+        let num_pairs = if num_elements == 0 {
+            1
+        } else {
+            1 << (num_elements - 1)
+        };
+        (0..num_pairs).map(move |mask| {
+            let subset = Subset::new(self, mask);
+            (subset, subset.complement())
+        })
+    }
+
+    fn powerset_product<'b, J: Index<usize> + SizableContainer + ?Sized>(
+        &'a self,
+        other: &'b J,
+    ) -> impl Iterator<Item = (Subset<'a, I>, Subset<'b, J>)>
+    where
+        J::Output: Sized,
+    {
+        self.powerset()
+            .flat_map(move |a| other.powerset().map(move |b| (a, b)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn powerset_chunks(&'a self, n: usize) -> Vec<SubsetIterator<'a, I>> {
+        assert!(n > 0, "cannot split the powerset into 0 chunks");
+
+        let powerset = self.powerset();
+        let (size, remainder) = (powerset.end / n, powerset.end % n);
+        let mut start = 0;
+        (0..n)
+            .map(|i| {
+                // The first chunks take one each of the subsets that don't divide evenly
+                let end = start + size + usize::from(i < remainder);
+                let chunk = powerset.clone().with_bounds(start, end);
+                start = end;
+                chunk
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn for_each_into<F: FnMut(&[I::Output])>(&self, mut f: F)
+    where
+        I::Output: Clone,
+    {
+        let mut buffer = Vec::with_capacity(self.num_elements());
+        for mask in 0..self.num_subsets() {
+            buffer.clear();
+            buffer.extend(Subset::new(self, mask).cloned());
+            f(&buffer);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn bucket_powerset(&'a self, m: usize) -> Vec<Vec<Subset<'a, I>>> {
+        assert!(m > 0, "cannot distribute the subsets into 0 buckets");
+
+        let mut buckets = (0..m).map(|_| Vec::new()).collect::<Vec<_>>();
+        for subset in self.powerset() {
+            buckets[subset.bitmask() % m].push(subset);
+        }
+        buckets
+    }
+
+    #[cfg(feature = "alloc")]
+    fn powerset_vecs(&self) -> Vec<Vec<I::Output>>
+    where
+        I::Output: Clone,
+    {
+        (0..self.num_subsets())
+            .map(|mask| Subset::new(self, mask).to_vec())
+            .collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn powerset_container(&'a self) -> PowersetContainer<'a, I> {
+        PowersetContainer::new(self.powerset().collect())
+    }
+
+    fn subset_at(&'a self, mask: usize) -> Option<Subset<'a, I>> {
+        if !self.fits() || mask & !full_mask::<usize>(self.num_elements()) != 0 {
+            return None;
+        }
+
+        Some(Subset::new(self, mask))
+    }
+
+    fn subset_from_bool_mask(&'a self, mask: &[bool]) -> Subset<'a, I> {
+        assert_supported::<usize>(self.num_elements());
+        assert_eq!(
+            self.num_elements(),
+            mask.len(),
+            "the mask has to have one entry per element"
+        );
+
+        let mask = mask
+            .iter()
+            .enumerate()
+            .filter(|&(_, &included)| included)
+            .fold(0, |mask, (i, _)| mask | 1 << i);
+        Subset::new(self, mask)
+    }
+
+    fn combinations(&'a self, k: usize) -> CombinationsIterator<'a, I> {
+        assert_supported::<usize>(self.num_elements());
+
+        CombinationsIterator::new(self, k)
+    }
+
+    fn num_combinations(&self, k: usize) -> u128 {
+        let n = self.num_elements();
+        if k > n {
+            return 0;
+        }
+
+        // C(n, k) == C(n, n - k), and the smaller one needs fewer steps
+        let k = k.min(n - k) as u128;
+        let n = n as u128;
+        (0..k).fold(1, |count: u128, i| {
+            // `count` is C(n, i), and `count * (n - i) / (i + 1)` is C(n, i + 1). Dividing out
+            // the common factor first keeps the intermediate product from overflowing.
+            let divisor = i + 1;
+            let common = gcd(count, divisor);
+            (count / common)
+                .checked_mul((n - i) / (divisor / common))
+                .expect("there are too many combinations to count in a u128")
+        })
+    }
+
+    fn pairs(&'a self) -> impl Iterator<Item = (&'a I::Output, &'a I::Output)> {
+        self.combinations(2).map(|mut pair| {
+            // Every subset has exactly two elements
+            (pair.next().unwrap(), pair.next().unwrap())
+        })
+    }
+
+    fn triples(&'a self) -> impl Iterator<Item = (&'a I::Output, &'a I::Output, &'a I::Output)> {
+        self.combinations(3).map(|mut triple| {
+            // Every subset has exactly three elements
+            (
+                triple.next().unwrap(),
+                triple.next().unwrap(),
+                triple.next().unwrap(),
+            )
+        })
+    }
+
+    fn powerset_sized(
+        &'a self,
+        sizes: RangeInclusive<usize>,
+    ) -> impl Iterator<Item = Subset<'a, I>> {
+        // There are no subsets larger than the container, so don't bother looking for them
+        let largest = (*sizes.end()).min(self.num_elements());
+        (*sizes.start()..=largest).flat_map(move |k| self.combinations(k))
+    }
+
+    fn powerset_by_size(&'a self) -> impl Iterator<Item = Subset<'a, I>> {
+        self.powerset_sized(0..=self.num_elements())
+    }
+
+    fn powerset_up_to(&'a self, max_size: usize) -> impl Iterator<Item = Subset<'a, I>> {
+        self.powerset_sized(0..=max_size)
+    }
+
+    fn powerset_lex(&'a self) -> impl Iterator<Item = Subset<'a, I>> {
+        assert_supported::<usize>(self.num_elements());
+
+        let n = self.num_elements();
+        core::iter::successors(Some(0), move |&mask| next_lex(mask, n))
+            .map(move |mask| Subset::new(self, mask))
+    }
+
+    #[cfg(feature = "std")]
+    fn write_powerset<W: Write>(&'a self, w: &mut W, sep: &str) -> io::Result<()>
+    where
+        I::Output: fmt::Display,
+    {
+        for subset in self.powerset() {
+            for (i, item) in subset.enumerate() {
+                if i > 0 {
+                    w.write_all(sep.as_bytes())?;
+                }
+                write!(w, "{}", item)?;
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "rand")]
+    fn random_subset<R: Rng>(&'a self, rng: &mut R) -> Subset<'a, I> {
+        assert_supported::<usize>(self.num_elements());
+
+        Subset::new(self, rng.gen_range(0..1 << self.num_elements()))
+    }
+
+    #[cfg(feature = "rand")]
+    fn random_subset_weighted<R: Rng>(&'a self, probs: &[f64], rng: &mut R) -> Subset<'a, I> {
+        assert_supported::<usize>(self.num_elements());
+        assert_eq!(
+            self.num_elements(),
+            probs.len(),
+            "there has to be one probability per element"
+        );
+
+        let mask = probs
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| rng.gen_bool(p))
+            .fold(0, |mask, (i, _)| mask | 1 << i);
+        Subset::new(self, mask)
+    }
+
+    #[cfg(feature = "rand")]
+    fn powerset_shuffled(&'a self, seed: u64) -> impl Iterator<Item = Subset<'a, I>> {
+        let num_elements = self.num_elements();
+        let mask = full_mask::<usize>(num_elements);
+
+        // Derive the round keys from the seed with splitmix64
+        let mut state = seed;
+        let mut keys = [0; 4];
+        for key in keys.iter_mut() {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            *key = (z ^ (z >> 31)) as usize;
+        }
+
+        (0..self.num_subsets()).map(move |i| {
+            // Every step is a bijection on the masks of `num_elements` bits, so together they
+            // permute the subsets
+            let shuffled = keys.iter().fold(i, |x, &key| {
+                let x = (x ^ key) & mask;
+                let x = x.wrapping_mul(key | 1) & mask;
+                x ^ (x >> (num_elements / 2 + 1))
+            });
+            Subset::new(self, shuffled)
+        })
+    }
+
+    #[cfg(all(feature = "rand", feature = "alloc"))]
+    fn sample_subsets<R: Rng>(&'a self, k: usize, rng: &mut R) -> Vec<Subset<'a, I>> {
+        let num_subsets = self.num_subsets();
+
+        rand::seq::index::sample(rng, num_subsets, k.min(num_subsets))
+            .into_iter()
+            .map(|mask| Subset::new(self, mask))
+            .collect()
+    }
+
+    #[cfg(feature = "bignum")]
+    fn powerset_big(&'a self) -> BigPowersetIterator<'a, I> {
+        BigPowersetIterator::new(self)
+    }
+
+    #[cfg(feature = "bignum")]
+    fn combinations_big(&'a self, k: usize) -> BigCombinationsIterator<'a, I> {
+        BigCombinationsIterator::new(self, k)
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_powerset(&'a self) -> impl IndexedParallelIterator<Item = Subset<'a, I>>
+    where
+        I: Sync,
+    {
+        assert_supported::<usize>(self.num_elements());
+
+        (0..1 << self.num_elements())
+            .into_par_iter()
+            .map(move |mask| Subset::new(self, mask))
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_fold<T, F, R>(&'a self, identity: T, fold: F, reduce: R) -> T
+    where
+        I: Sync,
+        T: Clone + Send + Sync,
+        F: Fn(T, Subset<'a, I>) -> T + Sync,
+        R: Fn(T, T) -> T + Sync,
+    {
+        (0..self.num_subsets())
+            .into_par_iter()
+            .fold(
+                || identity.clone(),
+                |acc, mask| fold(acc, Subset::new(self, mask)),
+            )
+            .reduce(|| identity.clone(), &reduce)
+    }
+
+    #[cfg(feature = "stream")]
+    fn powerset_stream(&'a self) -> impl Stream<Item = Subset<'a, I>> {
+        futures::stream::iter(self.powerset())
+    }
+}
+
+/// The largest number of elements a container can have for [`Powerset::powerset`] to work,
+/// i.e. `usize::BITS - 1` (63 on 64-bit targets). Use
+/// [`powerset_with`](Powerset::powerset_with) or `powerset_big` for larger containers.
+pub const fn max_supported_elements() -> u32 {
+    <usize as MaskStorage>::MAX_ELEMENTS
+}
+
+/// Panics if a container with `num_elements` elements is too large for a bitmask of type `M`,
+/// which for `usize` is when it has more than [`max_supported_elements`] elements
+fn assert_supported<M: MaskStorage>(num_elements: usize) {
+    assert!(
+        num_elements <= M::MAX_ELEMENTS as usize,
+        "cannot take the powerset of {} elements, at most {} are supported",
+        num_elements,
+        M::MAX_ELEMENTS
+    );
+}
+
+/// The bitmask selecting all of the `num_elements` elements
+fn full_mask<M: MaskStorage>(num_elements: usize) -> M {
+    if num_elements >= M::BITS as usize {
+        !M::ZERO
+    } else {
+        (M::ONE << num_elements) - M::ONE
+    }
+}
+
+/// The greatest common divisor of `a` and `b`
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// All the submasks of `mask`, from `mask` itself down to the empty mask
+fn submasks<M: MaskStorage>(mask: M) -> impl Iterator<Item = M> {
+    core::iter::successors(Some(mask), move |&sub| {
+        if sub == M::ZERO {
+            None
+        } else {
+            Some((sub - M::ONE) & mask)
+        }
+    })
+}
+
+/// All the submasks of `mask`, from the empty mask up to `mask` itself
+fn submasks_ascending<M: MaskStorage>(mask: M) -> impl Iterator<Item = M> {
+    core::iter::successors(Some(M::ZERO), move |&sub| {
+        if sub == mask {
+            None
+        } else {
+            // Setting all the bits outside of the mask makes the increment carry past them
+            Some(((sub | !mask) + M::ONE) & mask)
+        }
+    })
+}
+
+/// The subset following `mask` in lexicographic order, if there is one
+fn next_lex(mask: usize, num_elements: usize) -> Option<usize> {
+    // Index one past the highest element in the subset
+    let top = (usize::BITS - mask.leading_zeros()) as usize;
+
+    if top < num_elements {
+        // Extend with the next element
+        return Some(mask | 1 << top);
+    }
+    if mask == 0 {
+        // The container is empty
+        return None;
+    }
+
+    // The last element can't be extended, so drop it, and move the new last element one up
+    let rest = mask & !(1 << (top - 1));
+    if rest == 0 {
+        return None;
+    }
+
+    let last = (usize::BITS - 1 - rest.leading_zeros()) as usize;
+    Some(rest & !(1 << last) | 1 << (last + 1))
+}
+
+// References can't be indexed, but calling `powerset` on them goes through the container
+impl<C: SizableContainer + ?Sized> SizableContainer for &C {
+    fn num_elements(&self) -> usize {
+        (**self).num_elements()
+    }
+}
+
+// `Box<[T]>` itself can't be indexed, but calling `powerset` on it goes through the slice
+#[cfg(feature = "alloc")]
+impl<T> SizableContainer for Box<[T]> {
+    fn num_elements(&self) -> usize {
+        self.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> SizableContainer for Vec<T> {
+    fn num_elements(&self) -> usize {
+        self.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> SizableContainer for VecDeque<T> {
+    fn num_elements(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> SizableContainer for [T] {
+    fn num_elements(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, const N: usize> SizableContainer for [T; N] {
+    fn num_elements(&self) -> usize {
+        N
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Powerset;
+
+    #[test]
+    fn correct_subsets() {
+        let items = [1, 2, 3, 4];
+        let mut powerset = items.powerset();
+
+        // Test that we get all subsets of the array, and nothing more
+        assert_eq!(
+            Vec::<i32>::new(),
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![1],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![2],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![1, 2],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![3],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![1, 3],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![2, 3],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![1, 2, 3],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![4],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![1, 4],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![2, 4],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![1, 2, 4],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![3, 4],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![1, 3, 4],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![2, 3, 4],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![1, 2, 3, 4],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert!(powerset.next().is_none());
+    }
+
+    #[test]
+    fn size_hint() {
+        let items = [1, 2, 3, 4, 5, 6];
+
+        for mut set in items.powerset() {
+            let mut count = 0;
+            let size_hint = set.size_hint();
+            loop {
+                let elem = set.next();
+                if elem.is_none() {
+                    break;
+                }
+                count += 1;
+                assert_eq!(size_hint.0 - count, set.size_hint().0);
+            }
+
+            assert_eq!(count, size_hint.0);
+            assert_eq!(Some(count), size_hint.1);
+        }
+    }
+
+    #[test]
+    fn powerset_size_hint() {
+        let items = [1, 2, 3, 4, 5];
+        let mut powerset = items.powerset();
+
+        assert_eq!((32, Some(32)), powerset.size_hint());
+        for _ in 0..10 {
+            powerset.next();
+        }
+        assert_eq!((22, Some(22)), powerset.size_hint());
+        assert_eq!(22, powerset.collect::<Vec<_>>().len());
+    }
+
+    #[test]
+    fn exact_size() {
+        let items = [1, 2, 3, 4];
+        let mut powerset = items.powerset();
+
+        assert_eq!(16, powerset.len());
+        for remaining in (0..16).rev() {
+            assert!(powerset.next().is_some());
+            assert_eq!(remaining, powerset.len());
+        }
+
+        assert!(powerset.next().is_none());
+        assert_eq!(0, powerset.len());
+    }
+
+    #[test]
+    #[allow(clippy::iter_skip_next)]
+    fn nth() {
+        let items = [1, 2, 3, 4];
+
+        assert_eq!(
+            items.powerset().skip(5).next().unwrap().collect::<Vec<_>>(),
+            items.powerset().nth(5).unwrap().collect::<Vec<_>>()
+        );
+
+        let mut powerset = items.powerset();
+        assert_eq!(
+            vec![2],
+            powerset.nth(2).unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(13, powerset.len());
+        assert!(powerset.nth(13).is_none());
+        assert!(powerset.next().is_none());
+    }
+
+    #[test]
+    fn subset_at() {
+        let items = [1, 2, 3, 4];
+
+        assert_eq!(
+            vec![1, 2, 4],
+            items
+                .subset_at(0b1011)
+                .unwrap()
+                .cloned()
+                .collect::<Vec<i32>>()
+        );
+        assert_eq!(0, items.subset_at(0).unwrap().count());
+        assert_eq!(4, items.subset_at(0b1111).unwrap().count());
+        assert!(items.subset_at(0b10000).is_none());
+        assert!(items.subset_at(usize::MAX).is_none());
+
+        let too_large = vec![0; 100];
+        assert!(too_large.subset_at(0).is_none());
+        assert!(too_large.subset_at(1).is_none());
+    }
+
+    #[test]
+    fn nonempty_powerset() {
+        let items = [1, 2, 3, 4];
+        let mut powerset = items.nonempty_powerset();
+
+        assert_eq!(15, powerset.len());
+        assert_eq!(
+            vec![1],
+            powerset.next().unwrap().cloned().collect::<Vec<i32>>()
+        );
+        assert_eq!(14, powerset.count());
+        assert!(items.nonempty_powerset().all(|subset| subset.count() > 0));
+
+        assert_eq!(0, Vec::<i32>::new().nonempty_powerset().count());
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn random_subset() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let items = vec![1, 2, 3];
+        let mut rng = StdRng::seed_from_u64(8192);
+        let mut seen_empty = false;
+        let mut seen_full = false;
+
+        for _ in 0..1000 {
+            let subset = items.random_subset(&mut rng).cloned().collect::<Vec<i32>>();
+            assert!(subset.iter().all(|item| items.contains(item)));
+            seen_empty |= subset.is_empty();
+            seen_full |= subset == items;
+        }
+
+        assert!(seen_empty);
+        assert!(seen_full);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_powerset() {
+        use rayon::prelude::*;
+
+        let items = (1..=12).collect::<Vec<i32>>();
+
+        let sequential = items
+            .powerset()
+            .map(|subset| subset.cloned().collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+        let mut parallel = items
+            .par_powerset()
+            .map(|subset| subset.cloned().collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+        assert_eq!(sequential, parallel);
+
+        let mut sorted = sequential.clone();
+        sorted.sort();
+        parallel.sort();
+        assert_eq!(sorted, parallel);
+    }
+
+    #[test]
+    fn subset_exact_size() {
+        let items = [1, 2, 3, 4];
+        let mut subset = items.subset_at(0b1011).unwrap();
+
+        assert_eq!(3, subset.len());
+        assert_eq!(Some(&1), subset.next());
+        assert_eq!(2, subset.len());
+        assert_eq!(Some(&2), subset.next());
+        assert_eq!(1, subset.len());
+        assert_eq!(Some(&4), subset.next());
+        assert_eq!(0, subset.len());
+        assert_eq!(None, subset.next());
+    }
+
+    #[test]
+    fn cardinality() {
+        let items = [1, 2, 3, 4, 5];
+        let mut subset = items.subset_at(0b10110).unwrap();
+
+        assert_eq!(3, subset.cardinality());
+        subset.next();
+        assert_eq!(3, subset.cardinality());
+        assert_eq!(
+            items.subset_at(0b10110).unwrap().count(),
+            subset.cardinality()
+        );
+
+        for subset in items.powerset() {
+            assert_eq!(subset.cardinality(), subset.count());
+        }
+    }
+
+    #[test]
+    fn subset_double_ended() {
+        let items = [1, 2, 3, 4, 5];
+
+        for mask in [0, 0b1, 0b10000, 0b10110, 0b11111] {
+            let mut forward = items
+                .subset_at(mask)
+                .unwrap()
+                .cloned()
+                .collect::<Vec<i32>>();
+            forward.reverse();
+            let backward = items
+                .subset_at(mask)
+                .unwrap()
+                .rev()
+                .cloned()
+                .collect::<Vec<i32>>();
+            assert_eq!(forward, backward);
+        }
+
+        // The two ends should never hand out the same element
+        let mut subset = items.subset_at(0b10111).unwrap();
+        assert_eq!(Some(&1), subset.next());
+        assert_eq!(Some(&5), subset.next_back());
+        assert_eq!(2, subset.len());
+        assert_eq!(Some(&3), subset.next_back());
+        assert_eq!(Some(&2), subset.next());
+        assert_eq!(0, subset.len());
+        assert_eq!(None, subset.next());
+        assert_eq!(None, subset.next_back());
+    }
+
+    #[test]
+    #[allow(clippy::clone_on_copy)]
+    fn clone_subset() {
+        struct NotClone(Vec<i32>);
+
+        impl std::ops::Index<usize> for NotClone {
+            type Output = i32;
+            fn index(&self, index: usize) -> &i32 {
+                &self.0[index]
+            }
+        }
+
+        impl crate::SizableContainer for NotClone {
+            fn num_elements(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        let items = NotClone(vec![1, 2, 3, 4]);
+        let mut subset = items.subset_at(0b1101).unwrap();
+        subset.next();
+
+        let clone = subset.clone();
+        assert_eq!(2, clone.count());
+        assert_eq!(vec![3, 4], subset.cloned().collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn complement() {
+        let items = vec![1, 2, 3, 4];
+
+        for subset in items.powerset() {
+            let mut elements = subset.cloned().collect::<Vec<i32>>();
+            let complement = subset.complement().cloned().collect::<Vec<i32>>();
+
+            assert!(complement.iter().all(|item| !elements.contains(item)));
+            elements.extend(complement);
+            elements.sort();
+            assert_eq!(items, elements);
+        }
+
+        assert_eq!(4, items.subset_at(0).unwrap().complement().count());
+        assert_eq!(0, items.subset_at(0b1111).unwrap().complement().count());
+    }
+
+    #[test]
+    fn contains() {
+        let items = [1, 2, 3, 4];
+        let mut subset = items.subset_at(0b0101).unwrap();
+
+        assert!(subset.contains(0));
+        assert!(!subset.contains(1));
+        assert!(subset.contains(2));
+        assert!(!subset.contains(3));
+        assert!(!subset.contains(4));
+        assert!(!subset.contains(usize::MAX));
+
+        // Iterating doesn't change the membership
+        subset.next();
+        assert!(subset.contains(0));
+    }
+
+    #[test]
+    fn bitmask() {
+        let items = [1, 2, 3, 4];
+
+        // The sixth subset is the one with the first and third element
+        let subset = items.powerset().nth(5).unwrap();
+        assert_eq!(5, subset.bitmask());
+        assert_eq!(vec![1, 3], subset.cloned().collect::<Vec<i32>>());
+
+        for (i, subset) in items.powerset().enumerate() {
+            assert_eq!(i, subset.bitmask());
+        }
+    }
+
+    #[test]
+    fn array_powerset() {
+        let items = [10, 20, 30];
+
+        assert_eq!(
+            vec![
+                vec![],
+                vec![10],
+                vec![20],
+                vec![10, 20],
+                vec![30],
+                vec![10, 30],
+                vec![20, 30],
+                vec![10, 20, 30]
+            ],
+            items
+                .powerset()
+                .map(|subset| subset.cloned().collect::<Vec<i32>>())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn slice_powerset() {
+        let items = (1..=6).collect::<Vec<i32>>();
+        let slice: &[i32] = &items[2..5];
+
+        assert_eq!(8, slice.powerset().len());
+        assert_eq!(
+            vec![3, 5],
+            slice
+                .powerset()
+                .nth(5)
+                .unwrap()
+                .cloned()
+                .collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            vec![3, 4, 5],
+            slice
+                .powerset()
+                .next_back()
+                .unwrap()
+                .cloned()
+                .collect::<Vec<i32>>()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vec_deque_powerset() {
+        let items = vec![1, 2, 3, 4];
+        let mut deque = items
+            .iter()
+            .cloned()
+            .collect::<std::collections::VecDeque<i32>>();
+        deque.rotate_left(1);
+        deque.rotate_right(1);
+
+        assert_eq!(
+            items
+                .powerset()
+                .map(|subset| subset.cloned().collect::<Vec<i32>>())
+                .collect::<Vec<_>>(),
+            deque
+                .powerset()
+                .map(|subset| subset.cloned().collect::<Vec<i32>>())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn count() {
+        let items = [1, 2, 3];
+
+        assert_eq!(8, items.powerset().count());
+
+        let mut powerset = items.powerset();
+        powerset.next();
+        assert_eq!(7, powerset.count());
+    }
+
+    #[test]
+    fn display() {
+        let items = [1, 2, 3, 4, 5];
+
+        assert_eq!("{}", items.subset_at(0).unwrap().to_string());
+        assert_eq!("{2}", items.subset_at(0b100).unwrap().to_string());
+        assert_eq!("{1, 3, 4}", items.subset_at(0b11010).unwrap().to_string());
+
+        // Formatting doesn't care how far the subset has been iterated
+        let mut subset = items.subset_at(0b11111).unwrap();
+        subset.next();
+        assert_eq!("{0, 1, 2, 3, 4}", subset.to_string());
+        assert_eq!(4, subset.count());
+    }
+
+    #[test]
+    fn powerset_from() {
+        let items = [1, 2, 3, 4, 5];
+
+        let mut powerset = items.powerset();
+        let mut subsets = powerset
+            .by_ref()
+            .take(13)
+            .map(|subset| subset.cloned().collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+        let checkpoint = powerset.next().unwrap().bitmask();
+
+        subsets.extend(
+            items
+                .powerset_from(checkpoint)
+                .map(|subset| subset.cloned().collect::<Vec<i32>>()),
+        );
+        assert_eq!(
+            items
+                .powerset()
+                .map(|subset| subset.cloned().collect::<Vec<i32>>())
+                .collect::<Vec<_>>(),
+            subsets
+        );
+
+        assert_eq!(0, items.powerset_from(32).count());
+    }
+
+    #[test]
+    #[should_panic(expected = "only 32 subsets")]
+    fn powerset_from_out_of_range() {
+        let items = [1, 2, 3, 4, 5];
+        items.powerset_from(33);
+    }
+
+    #[test]
+    fn without_allocation() {
+        // Arrays and slices only need core
+        let items = [1, 2, 3, 4];
+        let mut sum = 0;
+        for subset in items[1..].powerset() {
+            for item in subset {
+                sum += item;
+            }
+        }
+
+        assert_eq!(4 * (2 + 3 + 4), sum);
+    }
+
+    #[test]
+    fn powerset_sized() {
+        let items = [1, 2, 3, 4, 5, 6];
+
+        // C(6, 2) + C(6, 3) + C(6, 4)
+        assert_eq!(15 + 20 + 15, items.powerset_sized(2..=4).count());
+        assert!(items
+            .powerset_sized(2..=4)
+            .all(|subset| (2..=4).contains(&subset.cardinality())));
+
+        assert_eq!(64, items.powerset_sized(0..=usize::MAX).count());
+        assert_eq!(1, items.powerset_sized(6..=10).count());
+        assert_eq!(0, items.powerset_sized(7..=10).count());
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = 4..=2;
+        assert_eq!(0, items.powerset_sized(empty).count());
+    }
+
+    #[test]
+    fn powerset_by_size() {
+        let items = [1, 2, 3, 4, 5];
+
+        let sizes = items
+            .powerset_by_size()
+            .map(|subset| subset.cardinality())
+            .collect::<Vec<usize>>();
+        assert_eq!(32, sizes.len());
+        assert!(sizes.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let mut masks = items
+            .powerset_by_size()
+            .map(|subset| subset.bitmask())
+            .collect::<Vec<usize>>();
+        masks.sort();
+        assert_eq!((0..32).collect::<Vec<usize>>(), masks);
+    }
+
+    #[test]
+    fn powerset_lex() {
+        let items = ['a', 'b', 'c'];
+
+        assert_eq!(
+            vec![
+                vec![],
+                vec!['a'],
+                vec!['a', 'b'],
+                vec!['a', 'b', 'c'],
+                vec!['a', 'c'],
+                vec!['b'],
+                vec!['b', 'c'],
+                vec!['c']
+            ],
+            items
+                .powerset_lex()
+                .map(|subset| subset.cloned().collect::<Vec<char>>())
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(1, Vec::<char>::new().powerset_lex().count());
+        assert_eq!(1 << 10, [0; 10].powerset_lex().count());
+    }
+
+    #[test]
+    fn subset_eq() {
+        let items = vec![1, 2, 3, 4];
+        let other = items.clone();
+
+        assert!(items.subset_at(0b0110) == items.subset_at(0b0110));
+        assert!(items.subset_at(0b0110) != items.subset_at(0b0111));
+        assert!(items.subset_at(0b0110) != other.subset_at(0b0110));
+
+        let mut subset = items.subset_at(0b0110).unwrap();
+        subset.next();
+        assert!(subset == items.subset_at(0b0110).unwrap());
+    }
+
+    #[test]
+    fn subset_hash() {
+        use std::collections::HashSet;
+
+        let items = [1, 2, 3, 4];
+
+        let mut set = HashSet::new();
+        set.insert(items.subset_at(0b1001).unwrap());
+        set.insert(items.powerset().nth(0b1001).unwrap());
+        assert_eq!(1, set.len());
+
+        set.extend(items.powerset());
+        assert_eq!(16, set.len());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn to_vec() {
+        let items = vec![1, 2, 3, 4];
+
+        for subset in items.powerset() {
+            assert_eq!(subset.cloned().collect::<Vec<i32>>(), subset.to_vec());
+        }
+
+        let mut subset = items.subset_at(0b1110).unwrap();
+        subset.next();
+        assert_eq!(vec![2, 3, 4], subset.to_vec());
+        assert_eq!(vec![3, 4], subset.cloned().collect::<Vec<i32>>());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn powerset_rev() {
+        let items = vec![1, 2, 3];
+        let subsets = items
+            .powerset_rev()
+            .map(|subset| subset.to_vec())
+            .collect::<Vec<_>>();
+
+        assert_eq!(8, subsets.len());
+        assert_eq!(vec![1, 2, 3], subsets[0]);
+        assert_eq!(vec![2, 3], subsets[1]);
+        assert_eq!(Vec::<i32>::new(), subsets[7]);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn check_mask_storage<M: crate::MaskStorage>() {
+        let items = vec![1, 2, 3, 4];
+
+        assert_eq!(
+            items
+                .powerset()
+                .map(|subset| subset.to_vec())
+                .collect::<Vec<_>>(),
+            items
+                .powerset_with::<M>()
+                .map(|subset| subset.to_vec())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(16, items.powerset_with::<M>().count());
+        assert_eq!((16, Some(16)), items.powerset_with::<M>().size_hint());
+
+        let subset = items.powerset_with::<M>().nth(0b1011).unwrap();
+        assert_eq!(M::from_usize(0b1011), Some(subset.bitmask()));
+        assert_eq!(3, subset.cardinality());
+        assert!(subset.contains(3));
+        assert!(!subset.contains(2));
+        assert_eq!("{0, 1, 3}", subset.to_string());
+        assert_eq!(vec![3], subset.complement().to_vec());
+        assert_eq!(vec![4, 2, 1], subset.rev().cloned().collect::<Vec<i32>>());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn mask_storage() {
+        check_mask_storage::<usize>();
+        check_mask_storage::<u8>();
+        check_mask_storage::<u128>();
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn wide_mask_storage() {
+        let items = (0..100).collect::<Vec<usize>>();
+        let mut powerset = items.powerset_with::<u128>();
+
+        assert_eq!((usize::MAX, None), powerset.size_hint());
+        assert_eq!(0, powerset.next().unwrap().count());
+
+        let full = powerset.next_back().unwrap();
+        assert_eq!(100, full.cardinality());
+        assert_eq!(items, full.to_vec());
+        assert_eq!(
+            vec![99, 98],
+            full.rev().take(2).cloned().collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 7")]
+    fn narrow_mask_storage() {
+        let items = [0; 8];
+        items.powerset_with::<u8>();
+    }
+
+    #[test]
+    fn disjoint_pairs() {
+        let items = [1, 2, 3];
+
+        assert_eq!(27, items.disjoint_pairs().count());
+        assert!(items
+            .disjoint_pairs()
+            .all(|(a, b)| a.bitmask() & b.bitmask() == 0));
+
+        let mut pairs = items
+            .disjoint_pairs()
+            .map(|(a, b)| (a.bitmask(), b.bitmask()))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs.dedup();
+        assert_eq!(27, pairs.len());
+
+        assert_eq!(81, [1, 2, 3, 4].disjoint_pairs().count());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn powerset_product() {
+        let first = vec![1, 2];
+        let second = vec!['a', 'b', 'c'];
+
+        let pairs = first
+            .powerset_product(&second)
+            .map(|(a, b)| (a.to_vec(), b.to_vec()))
+            .collect::<Vec<_>>();
+        assert_eq!(32, pairs.len());
+        assert_eq!((vec![], vec![]), pairs[0]);
+        assert_eq!((vec![], vec!['a', 'c']), pairs[5]);
+        assert_eq!((vec![2], vec!['b']), pairs[18]);
+        assert_eq!((vec![1, 2], vec!['a', 'b', 'c']), pairs[31]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn proper_powerset() {
+        let items = vec![1, 2, 3, 4];
+
+        assert_eq!(15, items.proper_powerset().len());
+        assert_eq!(15, items.proper_powerset().count());
+        assert!(items
+            .proper_powerset()
+            .all(|subset| subset.cardinality() < 4));
+        assert_eq!(
+            vec![2, 3, 4],
+            items.proper_powerset().next_back().unwrap().to_vec()
+        );
+
+        assert_eq!(0, Vec::<i32>::new().proper_powerset().count());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn set_operations() {
+        let items = vec![1, 2, 3, 4, 5];
+        let a = items.subset_at(0b00111).unwrap();
+        let b = items.subset_at(0b10110).unwrap();
+
+        assert_eq!(vec![1, 2, 3, 5], a.union(&b).to_vec());
+        assert_eq!(vec![2, 3], a.intersection(&b).to_vec());
+        assert_eq!(vec![1], a.difference(&b).to_vec());
+        assert_eq!(vec![5], b.difference(&a).to_vec());
+
+        // The result starts from the beginning, even if the operands are partially consumed
+        let mut c = items.subset_at(0b11000).unwrap();
+        c.next();
+        assert_eq!(vec![4, 5], c.intersection(&c.clone()).to_vec());
+        assert_eq!(Some(&1), c.union(&a).next());
+        assert_eq!(0, a.intersection(&c).count());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "same container")]
+    fn set_operations_different_containers() {
+        let items = vec![1, 2, 3];
+        let other = items.clone();
+
+        items
+            .subset_at(0b01)
+            .unwrap()
+            .union(&other.subset_at(0b10).unwrap());
+    }
+
+    #[test]
+    fn num_subsets() {
+        assert_eq!(16, [1, 2, 3, 4].num_subsets());
+        assert_eq!(1, Vec::<i32>::new().num_subsets());
+
+        let n = usize::BITS as usize - 1;
+        assert_eq!(1 << n, vec![0; n].num_subsets());
+    }
+
+    #[test]
+    fn empty_and_full() {
+        let items = [1, 2, 3];
+        let mut powerset = items.powerset();
+
+        let mut first = powerset.next().unwrap();
+        assert!(first.is_empty());
+        assert!(!first.is_full());
+        assert_eq!(None, first.next());
+        assert!(first.is_empty());
+
+        let mut last = powerset.next_back().unwrap();
+        assert!(!last.is_empty());
+        assert!(last.is_full());
+        last.next();
+        assert!(last.is_full());
+
+        assert!(powerset.all(|subset| !subset.is_empty() && !subset.is_full()));
+
+        // The only subset of an empty container is both
+        let empty = Vec::<i32>::new();
+        let subset = empty.powerset().next().unwrap();
+        assert!(subset.is_empty() && subset.is_full());
+    }
+
+    #[test]
+    fn fused() {
+        let items = [1, 2, 3];
+
+        let mut powerset = items.powerset();
+        powerset.by_ref().for_each(drop);
+        for _ in 0..5 {
+            assert!(powerset.next().is_none());
+            assert!(powerset.next_back().is_none());
+        }
+
+        let mut subset = items.subset_at(0b101).unwrap();
+        subset.by_ref().for_each(drop);
+        for _ in 0..5 {
+            assert!(subset.next().is_none());
+            assert!(subset.next_back().is_none());
+        }
+    }
+
+    #[test]
+    fn rank() {
+        let items = [1, 2, 3, 4];
+
+        for (k, subset) in items.powerset().enumerate() {
+            assert_eq!(k, subset.rank());
+            assert!(items.subset_at(subset.rank()).unwrap() == subset);
+        }
+    }
+
+    #[test]
+    fn clone_powerset() {
+        let items = [1, 2, 3, 4];
+        let mut powerset = items.powerset();
+        powerset.nth(6);
+
+        let fork = powerset.clone();
+        assert_eq!(9, fork.len());
+        assert!(fork.zip(powerset.by_ref()).all(|(a, b)| a == b));
+        assert!(powerset.next().is_none());
+    }
+
+    #[test]
+    fn try_powerset() {
+        use crate::PowersetError;
+
+        let items = [0; 10];
+        assert_eq!(1024, items.try_powerset().unwrap().len());
+
+        let items = vec![0; usize::BITS as usize];
+        let error = items.try_powerset().err().unwrap();
+        assert_eq!(PowersetError::TooLarge, error);
+        assert!(error.to_string().contains("too large"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "rand", feature = "alloc"))]
+    fn sample_subsets() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let items = vec![1, 2, 3, 4, 5];
+        let mut rng = StdRng::seed_from_u64(8192);
+
+        let samples = items.sample_subsets(10, &mut rng);
+        assert_eq!(10, samples.len());
+        assert!(samples.iter().all(|subset| subset.bitmask() < 32));
+
+        let mut masks = samples
+            .iter()
+            .map(|subset| subset.bitmask())
+            .collect::<Vec<usize>>();
+        masks.sort();
+        masks.dedup();
+        assert_eq!(10, masks.len());
+
+        let mut all = items
+            .sample_subsets(100, &mut rng)
+            .iter()
+            .map(|subset| subset.bitmask())
+            .collect::<Vec<usize>>();
+        all.sort();
+        assert_eq!((0..32).collect::<Vec<usize>>(), all);
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn random_subset_weighted() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let items = [1, 2, 3, 4];
+        let mut rng = StdRng::seed_from_u64(8192);
+
+        for _ in 0..100 {
+            let subset = items.random_subset_weighted(&[1.0, 0.0, 0.5, 1.0], &mut rng);
+            assert!(subset.contains(0));
+            assert!(!subset.contains(1));
+            assert!(subset.contains(3));
+        }
+
+        let subset = items.random_subset_weighted(&[0.0; 4], &mut rng);
+        assert!(subset.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    #[should_panic(expected = "one probability per element")]
+    fn random_subset_weighted_wrong_length() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let items = [1, 2, 3, 4];
+        items.random_subset_weighted(&[0.5; 3], &mut StdRng::seed_from_u64(8192));
+    }
+
+    #[test]
+    fn collect_elements() {
+        use std::collections::BTreeSet;
+
+        let items = [3, 1, 4, 1, 5];
+        let subset = items.subset_at(0b11011).unwrap();
+
+        let vec: Vec<i32> = subset.collect_elements();
+        assert_eq!(vec![3, 1, 1, 5], vec);
+
+        let set: BTreeSet<i32> = subset.collect_elements();
+        assert_eq!(vec![1, 3, 5], set.into_iter().collect::<Vec<i32>>());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn map_to_vec() {
+        let items = [1, 2, 3, 4, 5];
+        let mut subset = items.subset_at(0b10101).unwrap();
+        subset.next();
+
+        assert_eq!(vec![1, 9, 25], subset.map_to_vec(|x| x * x));
+        assert_eq!(vec![&3, &5], subset.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn indices() {
+        let items = [0; 6];
+        let subset = items.subset_at(0b10110).unwrap();
+        assert_eq!(vec![1, 2, 4], subset.indices().collect::<Vec<usize>>());
+
+        let empty = items.subset_at(0).unwrap();
+        assert_eq!(None, empty.indices().next());
+
+        let wide = vec![0; 100];
+        let subset = wide.powerset_with::<u128>().next_back().unwrap();
+        assert_eq!(
+            (0..100).collect::<Vec<usize>>(),
+            subset.indices().collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn bool_mask() {
+        let items = ['a', 'b', 'c', 'd'];
+        let subset = items.subset_at(0b1010).unwrap();
+
+        let mask = subset.to_bool_mask();
+        assert_eq!(vec![false, true, false, true], mask);
+        assert!(subset == items.subset_from_bool_mask(&mask));
+
+        for subset in items.powerset() {
+            assert!(subset == items.subset_from_bool_mask(&subset.to_bool_mask()));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "one entry per element")]
+    fn bool_mask_wrong_length() {
+        let items = ['a', 'b', 'c', 'd'];
+        items.subset_from_bool_mask(&[true, false]);
+    }
+
+    #[test]
+    fn powerset_containing() {
+        let items = [1, 2, 3, 4, 5];
+
+        let subsets = items.powerset_containing(2).collect::<Vec<_>>();
+        assert_eq!(16, subsets.len());
+        assert!(subsets.iter().all(|subset| subset.contains(2)));
+        assert!(subsets.windows(2).all(|w| w[0].bitmask() < w[1].bitmask()));
+
+        let single = [1];
+        assert_eq!(
+            vec![0b1],
+            single
+                .powerset_containing(0)
+                .map(|subset| subset.bitmask())
+                .collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot require element 5")]
+    fn powerset_containing_out_of_range() {
+        let items = [1, 2, 3, 4, 5];
+        let _ = items.powerset_containing(5);
+    }
+
+    #[test]
+    fn constrained_powerset() {
+        let items = [1, 2, 3, 4];
+
+        let subsets = items.constrained_powerset(&[0], &[3]).collect::<Vec<_>>();
+        assert_eq!(4, subsets.len());
+        assert!(subsets
+            .iter()
+            .all(|subset| subset.contains(0) && !subset.contains(3)));
+
+        assert_eq!(16, items.constrained_powerset(&[], &[]).count());
+        assert_eq!(1, items.constrained_powerset(&[0, 1], &[2, 3]).count());
+    }
+
+    #[test]
+    #[should_panic(expected = "both forced in and forced out")]
+    fn constrained_powerset_overlap() {
+        let items = [1, 2, 3, 4];
+        let _ = items.constrained_powerset(&[0, 1], &[1]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[allow(clippy::double_ended_iterator_last)]
+    fn last() {
+        let items = vec![1, 2, 3];
+
+        let last = items.powerset().last().unwrap();
+        assert_eq!(vec![1, 2, 3], last.to_vec());
+        assert!(last.is_full());
+
+        let last = items.proper_powerset().last().unwrap();
+        assert_eq!(vec![2, 3], last.to_vec());
+
+        let mut powerset = items.powerset();
+        powerset.by_ref().for_each(drop);
+        assert!(powerset.last().is_none());
+
+        let wide = vec![0; 100];
+        assert_eq!(100, wide.powerset_with::<u128>().last().unwrap().count());
+    }
+
+    #[test]
+    fn ord() {
+        let items = [1, 2, 3];
+        let mut subsets = items.powerset().rev().collect::<Vec<_>>();
+        subsets.sort();
+
+        assert_eq!(
+            vec![0b000, 0b001, 0b010, 0b100, 0b011, 0b101, 0b110, 0b111],
+            subsets
+                .iter()
+                .map(|subset| subset.bitmask())
+                .collect::<Vec<usize>>()
+        );
+        assert!(subsets
+            .iter()
+            .zip(items.powerset_by_size())
+            .all(|(a, b)| *a == b));
+
+        let other = [1, 2, 3];
+        let a = items.subset_at(0b11).unwrap();
+        let b = other.subset_at(0b11).unwrap();
+        assert_ne!(core::cmp::Ordering::Equal, Ord::cmp(&a, &b));
+        assert!(items.subset_at(0b100).unwrap() < items.subset_at(0b011).unwrap());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn powerset_chunks() {
+        let items = [1, 2, 3, 4, 5];
+        let sequential = items.powerset().collect::<Vec<_>>();
+
+        for n in 1..=40 {
+            let chunks = items.powerset_chunks(n);
+            assert_eq!(n, chunks.len());
+
+            let lengths = chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>();
+            assert!(lengths.iter().max().unwrap() - lengths.iter().min().unwrap() <= 1);
+
+            assert!(chunks.into_iter().flatten().eq(sequential.iter().cloned()));
+        }
+
+        // The slice method is left alone
+        assert_eq!(
+            vec![&[1, 2][..], &[3, 4], &[5]],
+            items.chunks(2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn powerset_shuffled() {
+        let items = [1, 2, 3, 4, 5, 6];
+
+        let order = |seed| {
+            items
+                .powerset_shuffled(seed)
+                .map(|subset| subset.bitmask())
+                .collect::<Vec<usize>>()
+        };
+
+        assert_eq!(order(42), order(42));
+        assert_ne!(order(42), order(43));
+        assert_ne!((0..64).collect::<Vec<usize>>(), order(42));
+
+        let mut sorted = order(42);
+        sorted.sort_unstable();
+        assert_eq!((0..64).collect::<Vec<usize>>(), sorted);
+
+        let empty: [i32; 0] = [];
+        assert_eq!(1, empty.powerset_shuffled(7).count());
+    }
+
+    #[test]
+    fn num_combinations() {
+        let items = [1, 2, 3, 4, 5];
+        assert_eq!(10, items.num_combinations(2));
+        assert_eq!(
+            items.combinations(2).count() as u128,
+            items.num_combinations(2)
+        );
+
+        for k in 0..=6 {
+            assert_eq!(
+                items.combinations(k).count() as u128,
+                items.num_combinations(k)
+            );
+        }
+
+        let many = [(); 200];
+        assert_eq!(200 * 199 / 2, many.num_combinations(2));
+        assert_eq!(1, many.num_combinations(200));
+        assert_eq!(
+            95067625827960698145584333020095113100,
+            many[..130].num_combinations(65)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "too many combinations")]
+    fn num_combinations_overflow() {
+        let many = [(); 200];
+        many.num_combinations(100);
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_fold)]
+    fn sum_elements() {
+        let items = [3, 1, 4, 1, 5, 9];
+        let mut subset = items.subset_at(0b101101).unwrap();
+        subset.next();
+
+        let manual = [3, 4, 1, 9].iter().fold(0, |sum, x| sum + x);
+        assert_eq!(manual, subset.sum_elements::<i32>());
+        assert_eq!(3, subset.len());
+        assert_eq!(14, subset.sum::<i32>());
+    }
+
+    #[test]
+    fn borrowed_into_iter() {
+        let items = [String::from("a"), String::from("b"), String::from("c")];
+        let subset = items.subset_at(0b101).unwrap();
+
+        let mut first = Vec::new();
+        for item in &subset {
+            first.push(item);
+        }
+        let second = (&subset).into_iter().collect::<Vec<String>>();
+
+        assert_eq!(vec![String::from("a"), String::from("c")], first);
+        assert_eq!(first, second);
+        assert_eq!(2, subset.count());
+    }
+
+    #[test]
+    fn sparse_subset() {
+        let items = (0..127).collect::<Vec<u32>>();
+        let subset = crate::Subset::new(&items, 1u128 << 3 | 1 << 64 | 1 << 120);
+
+        assert_eq!(vec![&3, &64, &120], subset.collect::<Vec<_>>());
+        assert_eq!(vec![&120, &64, &3], subset.rev().collect::<Vec<_>>());
+
+        let mut subset = subset;
+        assert_eq!(Some(&3), subset.next());
+        assert_eq!(Some(&120), subset.next_back());
+        assert_eq!(Some(&64), subset.next());
+        assert_eq!(None, subset.next_back());
+        assert_eq!(None, subset.next());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn boxed_slice_powerset() {
+        fn size<C: crate::SizableContainer + ?Sized>(container: &C) -> usize {
+            container.num_elements()
+        }
+
+        let vec = vec![1, 2, 3];
+        let boxed = vec.clone().into_boxed_slice();
+        assert_eq!(3, size(&boxed));
+
+        assert!(boxed
+            .powerset()
+            .map(|subset| subset.to_vec())
+            .eq(vec.powerset().map(|subset| subset.to_vec())));
+    }
+
+    #[test]
+    fn complement_pairs() {
+        let items = [1, 2, 3];
+        let pairs = items.complement_pairs().collect::<Vec<_>>();
+        assert_eq!(4, pairs.len());
+
+        for (a, b) in &pairs {
+            assert!(a.intersection(b).is_empty());
+            assert!(a.union(b).is_full());
+        }
+        assert!(pairs[0].0.is_empty() && pairs[0].1.is_full());
+
+        let mut seen = pairs
+            .iter()
+            .flat_map(|(a, b)| vec![a.bitmask(), b.bitmask()])
+            .collect::<Vec<usize>>();
+        seen.sort_unstable();
+        assert_eq!((0..8).collect::<Vec<usize>>(), seen);
+
+        let empty: [i32; 0] = [];
+        assert_eq!(1, empty.complement_pairs().count());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_powerset() {
+        let items = ["a", "b", "c"];
+        let mut buffer = Vec::new();
+        items.write_powerset(&mut buffer, ", ").unwrap();
+
+        assert_eq!(
+            "\na\nb\na, b\nc\na, c\nb, c\na, b, c\n",
+            String::from_utf8(buffer).unwrap()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_powerset_with_io_write() {
+        // `Powerset` is implemented for `Vec<u8>` too, and must not shadow `io::Write`
+        use std::io::Write;
+
+        let mut buffer = Vec::new();
+        buffer.write_all(b"subsets:\n").unwrap();
+        ["a"].write_powerset(&mut buffer, ", ").unwrap();
+        assert_eq!("subsets:\n\na\n", String::from_utf8(buffer).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "stream")]
+    fn powerset_stream() {
+        use futures::StreamExt;
+
+        let items = [1, 2, 3, 4];
+        let streamed = futures::executor::block_on(
+            items
+                .powerset_stream()
+                .then(|subset| async move { subset.cloned().collect::<Vec<i32>>() })
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(
+            items
+                .powerset()
+                .map(|subset| subset.cloned().collect::<Vec<i32>>())
+                .collect::<Vec<_>>(),
+            streamed
+        );
+    }
+
+    #[test]
+    fn powerset_up_to() {
+        let items = (0..10).collect::<Vec<i32>>();
+
+        for max_size in 0..=11 {
+            let expected = (0..=max_size)
+                .map(|k| items.num_combinations(k))
+                .sum::<u128>();
+            assert_eq!(expected, items.powerset_up_to(max_size).count() as u128);
+            assert!(items
+                .powerset_up_to(max_size)
+                .all(|subset| subset.cardinality() <= max_size));
+        }
+
+        let large = vec![0; 60];
+        assert_eq!(1 + 60 + 60 * 59 / 2, large.powerset_up_to(2).count());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn filter_elements() {
+        let items = [1, 2, 3, 4, 5, 6];
+        let mut subset = items.subset_at(0b011110).unwrap();
+        subset.next();
+
+        let even = subset.filter_elements(|x| x % 2 == 0);
+        assert_eq!(vec![2, 4], even.to_vec());
+        assert!(even.difference(&subset).is_empty());
+
+        assert!(subset.filter_elements(|_| false).is_empty());
+        assert_eq!(subset.bitmask(), subset.filter_elements(|_| true).bitmask());
+    }
+
+    #[test]
+    fn powerset_enumerated() {
+        let items = ['a', 'b', 'c', 'd'];
+
+        let (mask, first) = items.powerset_enumerated().next().unwrap();
+        assert_eq!(0, mask);
+        assert!(first.is_empty());
+
+        for (k, (mask, subset)) in items.powerset_enumerated().enumerate() {
+            assert_eq!(k, mask);
+            assert!(subset == items.subset_at(k).unwrap());
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn reference_powerset() {
+        fn size<C: crate::SizableContainer>(container: C) -> usize {
+            container.num_elements()
+        }
+
+        struct Holder<'a> {
+            items: &'a Vec<i32>,
+        }
+
+        let items = vec![1, 2, 3];
+        let holder = Holder { items: &items };
+        assert_eq!(3, size(holder.items));
+        assert_eq!(3, size(&items[..]));
+
+        assert!(holder
+            .items
+            .powerset()
+            .map(|subset| subset.to_vec())
+            .eq(items.powerset().map(|subset| subset.to_vec())));
+        let slice: &[i32] = &items;
+        assert_eq!(8, slice.powerset().count());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn symmetric_difference() {
+        let items = [1, 2, 3, 4, 5];
+        let mut a = items.subset_at(0b01011).unwrap();
+        let b = items.subset_at(0b11001).unwrap();
+        a.next();
+
+        let both = a.symmetric_difference(&b);
+        assert_eq!(vec![2, 5], both.to_vec());
+        assert_eq!(both.bitmask(), b.symmetric_difference(&a).bitmask());
+        assert!(a.symmetric_difference(&a).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn with_without() {
+        let items = ['a', 'b', 'c', 'd'];
+        let mut subset = items.subset_at(0b0101).unwrap();
+        subset.next();
+
+        let added = subset.with(1);
+        assert_eq!(vec!['a', 'b', 'c'], added.to_vec());
+        assert_eq!(0b0101, subset.with(2).bitmask());
+
+        let removed = added.without(0);
+        assert_eq!(vec!['b', 'c'], removed.to_vec());
+        assert_eq!(0b0110, removed.without(3).bitmask());
+
+        assert!(subset.without(0).without(2).is_empty());
+        assert!(subset.with(1).with(3).is_full());
+    }
+
+    #[test]
+    #[should_panic(expected = "index 4 is out of range")]
+    fn with_out_of_range() {
+        let items = ['a', 'b', 'c', 'd'];
+        items.subset_at(0).unwrap().with(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 70 is out of range for a mask of 8 bits")]
+    fn with_beyond_mask() {
+        let items = [0; 100];
+        let _ = crate::Subset::new(&items, 0u8).with(70);
+    }
+
+    #[test]
+    fn copy_subset() {
+        fn total(subset: crate::Subset<'_, [i32; 4]>) -> i32 {
+            subset.sum()
+        }
+
+        let items = [1, 2, 3, 4];
+        let mut subset = items.subset_at(0b1011).unwrap();
+        assert_eq!(7, total(subset));
+        assert_eq!(7, total(subset));
+
+        subset.next();
+        let copy = subset;
+        assert_eq!(6, total(copy));
+        assert_eq!(Some(&2), subset.next());
+    }
+
+    #[test]
+    fn progress() {
+        let items = (0..10).collect::<Vec<i32>>();
+        let mut powerset = items.powerset();
+        assert_eq!(1024, powerset.remaining());
+        assert_eq!(0.0, powerset.progress());
+
+        let mut last = powerset.progress();
+        while powerset.next().is_some() {
+            assert!(powerset.progress() > last);
+            assert_eq!(powerset.len(), powerset.remaining());
+            last = powerset.progress();
+        }
+        assert_eq!(0, powerset.remaining());
+        assert_eq!(1.0, powerset.progress());
+
+        let mut powerset = items.powerset();
+        powerset.nth(511);
+        assert_eq!(0.5, powerset.progress());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn powerset_vecs() {
+        let items = vec![1, 2, 3];
+        let manual = items
+            .powerset()
+            .map(|subset| subset.cloned().collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+
+        assert_eq!(manual, items.powerset_vecs());
+        assert_eq!(
+            vec![vec![], vec![1], vec![2], vec![1, 2]],
+            [1, 2].powerset_vecs()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn nested_powerset() {
+        let items = vec![vec![1], vec![2, 3]];
+
+        let subsets = items
+            .powerset()
+            .map(|subset| subset.collect::<Vec<&Vec<i32>>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                vec![],
+                vec![&items[0]],
+                vec![&items[1]],
+                vec![&items[0], &items[1]]
+            ],
+            subsets
+        );
+
+        let full = items.subset_at(0b11).unwrap();
+        assert_eq!(vec![vec![1], vec![2, 3]], full.to_owned_vec());
+        assert_eq!(full.to_vec(), full.to_owned_vec());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn unsized_owned_vec() {
+        struct Words(Vec<String>);
+
+        impl std::ops::Index<usize> for Words {
+            type Output = str;
+            fn index(&self, index: usize) -> &str {
+                &self.0[index]
+            }
+        }
+
+        impl crate::SizableContainer for Words {
+            fn num_elements(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        let words = Words(vec![
+            String::from("a"),
+            String::from("b"),
+            String::from("c"),
+        ]);
+        assert_eq!(
+            vec![String::from("a"), String::from("c")],
+            crate::Subset::new(&words, 0b101usize).to_owned_vec()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn seek() {
+        let items = ['a', 'b', 'c', 'd'];
+        let mut powerset = items.powerset();
+
+        powerset.seek(3);
+        assert_eq!(vec!['a', 'b'], powerset.next().unwrap().to_vec());
+        assert_eq!(0b100, powerset.next().unwrap().bitmask());
+
+        powerset.nth(5);
+        powerset.seek(1);
+        assert_eq!(vec!['a'], powerset.next().unwrap().to_vec());
+        assert_eq!(14, powerset.len());
+
+        let mut chunk = items.powerset_chunks(2).remove(0);
+        chunk.seek(5);
+        assert_eq!(3, chunk.len());
+        chunk.next_back();
+        chunk.seek(6);
+        assert_eq!(0b110, chunk.next().unwrap().bitmask());
+        assert_eq!(None, chunk.next());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot seek to subset 16")]
+    fn seek_out_of_range() {
+        let items = ['a', 'b', 'c', 'd'];
+        items.powerset().seek(16);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic(expected = "cannot seek to subset 15, the iterator covers subsets 0..8")]
+    fn seek_past_chunk() {
+        let items = [1, 2, 3, 4];
+        items.powerset_chunks(2).remove(0).seek(15);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic(expected = "cannot seek to subset 2, the iterator covers subsets 8..16")]
+    fn seek_before_chunk() {
+        let items = [1, 2, 3, 4];
+        items.powerset_chunks(2).remove(1).seek(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot seek to subset 15, the iterator covers subsets 0..15")]
+    fn seek_after_next_back() {
+        let items = [1, 2, 3, 4];
+        let mut powerset = items.powerset();
+        powerset.next_back();
+        powerset.seek(15);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn bucket_powerset() {
+        let items = [1, 2, 3];
+        let buckets = items.bucket_powerset(3);
+        assert_eq!(3, buckets.len());
+
+        let masks = buckets
+            .iter()
+            .map(|bucket| {
+                bucket
+                    .iter()
+                    .map(|subset| subset.bitmask())
+                    .collect::<Vec<usize>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec![vec![0, 3, 6], vec![1, 4, 7], vec![2, 5]], masks);
+
+        let mut all = buckets.into_iter().flatten().collect::<Vec<_>>();
+        all.sort_by_key(|subset| subset.bitmask());
+        assert!(all.into_iter().eq(items.powerset()));
+    }
+
+    #[test]
+    fn try_for_each_subset() {
+        let items = [1, 2, 3, 4];
+
+        let mut visited = Vec::new();
+        let result = items.powerset().try_for_each_subset(|subset| {
+            visited.push(subset.bitmask());
+            if visited.len() == 4 {
+                Err(subset.bitmask())
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(Err(3), result);
+        assert_eq!(vec![0, 1, 2, 3], visited);
+
+        let ok: Result<(), ()> = items.powerset().try_for_each_subset(|_| Ok(()));
+        assert_eq!(Ok(()), ok);
+
+        let mut powerset = items.powerset();
+        let sum = powerset.try_fold(0, |sum, subset| {
+            if subset.is_full() {
+                None
+            } else {
+                Some(sum + subset.cardinality())
+            }
+        });
+        assert_eq!(None, sum);
+        assert_eq!(0, powerset.len());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn best_subset() {
+        let items = vec![3, -2, 5, -1, 4];
+
+        let best = items.best_subset(|subset| subset.sum::<i32>()).unwrap();
+        assert_eq!(vec![3, 5, 4], best.to_vec());
+
+        let capped = items
+            .best_subset(|subset| {
+                if subset.cardinality() <= 2 {
+                    Some(subset.sum::<i32>())
+                } else {
+                    None
+                }
+            })
+            .unwrap();
+        assert_eq!(vec![5, 4], capped.to_vec());
+
+        let empty: [i32; 0] = [];
+        assert!(empty
+            .best_subset(|subset| subset.cardinality())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn subset_iter() {
+        let items = ['a', 'b', 'c', 'd', 'e'];
+        let mut subset = items.subset_at(0b11010).unwrap();
+        subset.next();
+
+        assert_eq!(3, subset.cardinality());
+        assert_eq!(3, subset.iter().count());
+        assert_eq!(vec![&'b', &'d', &'e'], subset.iter().collect::<Vec<_>>());
+        assert_eq!(vec![&'d', &'e'], subset.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn reset() {
+        let items = [1, 2, 3, 4];
+        let mut subset = items.subset_at(0b1110).unwrap();
+
+        assert_eq!(Some(&2), subset.next());
+        assert_eq!(Some(&3), subset.next());
+        subset.reset();
+        assert_eq!(vec![&2, &3, &4], subset.collect::<Vec<_>>());
+
+        subset.next_back();
+        subset.reset();
+        assert_eq!(3, subset.len());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn zero_sized_elements() {
+        let items = vec![(); 3];
+        assert_eq!(8, items.powerset().count());
+
+        for subset in items.powerset() {
+            assert_eq!(subset.bitmask().count_ones() as usize, subset.cardinality());
+            assert_eq!(subset.cardinality(), subset.count());
+        }
+
+        let full = items.powerset().next_back().unwrap();
+        assert_eq!(vec![(), (), ()], full.to_vec());
+        assert_eq!(3, full.rev().count());
+        assert_eq!(3, items.combinations(1).count());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn supersets() {
+        let items = ['a', 'b', 'c'];
+        let subset = items.subset_at(0b001).unwrap();
+
+        assert_eq!(
+            vec![0b001, 0b011, 0b101, 0b111],
+            subset
+                .supersets()
+                .map(|superset| superset.bitmask())
+                .collect::<Vec<usize>>()
+        );
+        assert_eq!(8, items.subset_at(0).unwrap().supersets().count());
+        assert_eq!(1, items.subset_at(0b111).unwrap().supersets().count());
+
+        let wide = vec![0; 127];
+        let almost_full = crate::Subset::new(&wide, !0u128 >> 2);
+        assert_eq!(2, almost_full.supersets().count());
+    }
+
+    #[test]
+    fn subset_submasks() {
+        let items = ['a', 'b', 'c', 'd'];
+        let subset = items.subset_at(0b1010).unwrap();
+
+        assert_eq!(
+            vec![0b1010, 0b1000, 0b0010, 0b0000],
+            subset
+                .submasks()
+                .map(|sub| sub.bitmask())
+                .collect::<Vec<usize>>()
+        );
+        assert_eq!(1, items.subset_at(0).unwrap().submasks().count());
+        assert_eq!(16, items.subset_at(0b1111).unwrap().submasks().count());
+    }
+
+    #[test]
+    #[cfg(feature = "smallvec")]
+    fn to_smallvec() {
+        let items = [1, 2, 3, 4, 5];
+        let subset = items.subset_at(0b10110).unwrap();
+
+        let small = subset.to_smallvec::<4>();
+        assert_eq!(&[2, 3, 5], small.as_slice());
+        assert!(!small.spilled());
+
+        let spilled = subset.to_smallvec::<2>();
+        assert_eq!(&[2, 3, 5], spilled.as_slice());
+        assert!(spilled.spilled());
+    }
+
+    #[test]
+    fn powerset_into_iter() {
+        let items = [1, 2, 3];
+
+        let mut by_value = 0;
+        for subset in items.powerset() {
+            by_value += subset.cardinality();
+        }
+        assert_eq!(12, by_value);
+
+        let mut powerset = items.powerset();
+        powerset.next();
+        let first = (&powerset).into_iter().count();
+        let mut second = 0;
+        for _ in &powerset {
+            second += 1;
+        }
+        assert_eq!((7, 7), (first, second));
+        assert_eq!(7, powerset.len());
+    }
+
+    #[test]
+    fn fits() {
+        let max = crate::max_supported_elements();
+        assert_eq!(usize::BITS - 1, max);
+
+        let largest = vec![(); max as usize];
+        assert!(largest.fits());
+        assert!(largest.try_powerset().is_ok());
+
+        let too_large = vec![(); max as usize + 1];
+        assert!(!too_large.fits());
+        assert!(too_large.try_powerset().is_err());
+
+        let empty: [(); 0] = [];
+        assert!(empty.fits());
+    }
+
+    #[test]
+    fn eq_slice() {
+        let items = [1, 2, 3, 4];
+        let mut subset = items.subset_at(0b1101).unwrap();
+        subset.next();
+
+        assert!(subset.eq_slice(&[1, 3, 4]));
+        assert!(!subset.eq_slice(&[3, 4]));
+        assert!(!subset.eq_slice(&[1, 4, 3]));
+        assert!(!subset.eq_slice(&[1, 3, 4, 5]));
+        assert!(items.subset_at(0).unwrap().eq_slice(&[]));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn for_each_into() {
+        let items = vec![1, 2, 3, 4];
+
+        let mut sums = Vec::new();
+        items.for_each_into(|subset| sums.push(subset.iter().sum::<i32>()));
+        assert_eq!(
+            items
+                .powerset()
+                .map(|subset| subset.sum::<i32>())
+                .collect::<Vec<_>>(),
+            sums
+        );
+        assert_eq!(80, sums.iter().sum::<i32>());
+
+        let mut seen = Vec::new();
+        [1, 2].for_each_into(|subset| seen.push(subset.to_vec()));
+        assert_eq!([1, 2].powerset_vecs(), seen);
+    }
+
+    #[test]
+    fn debug() {
+        let items = [1, 2, 3, 4];
+        let subset = items.subset_at(0b1101).unwrap();
+        assert_eq!(
+            "Subset { mask: 0b1101, cardinality: 3 }",
+            format!("{:?}", subset)
+        );
+        assert_eq!(items.subset_at(0b1101), Some(subset));
+
+        let mut powerset = items.powerset();
+        powerset.nth(2);
+        assert_eq!(
+            "SubsetIterator { num_elements: 4, subset: 0b11, end: 0b10000 }",
+            format!("{:?}", powerset)
+        );
+        assert!(format!("{:#?}", powerset).contains("num_elements: 4,"));
+    }
+
+    #[test]
+    fn pairs_triples() {
+        let items = ['a', 'b', 'c', 'd'];
+
+        let pairs = items.pairs().collect::<Vec<_>>();
+        assert_eq!(6, pairs.len());
+        assert_eq!((&'a', &'b'), pairs[0]);
+        assert!(pairs.contains(&(&'b', &'d')));
+        assert!(pairs.iter().all(|(x, y)| x < y));
+
+        let triples = items.triples().collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (&'a', &'b', &'c'),
+                (&'a', &'b', &'d'),
+                (&'a', &'c', &'d'),
+                (&'b', &'c', &'d'),
+            ],
+            triples
+        );
+
+        assert_eq!(0, ['a', 'b'].triples().count());
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_fold() {
+        let items = (1..=16).collect::<Vec<u32>>();
+        let threshold = 70;
+
+        let parallel = items.par_fold(
+            0,
+            |count, subset| count + usize::from(subset.sum::<u32>() > threshold),
+            |a, b| a + b,
+        );
+        let sequential = items
+            .powerset()
+            .filter(|subset| subset.sum::<u32>() > threshold)
+            .count();
+        assert_eq!(sequential, parallel);
+        assert!(parallel > 0);
+
+        let total = items.par_fold(0, |sum, subset| sum + subset.cardinality(), |a, b| a + b);
+        assert_eq!(16 << 15, total);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn progress_of_chunk() {
+        let items = [1, 2, 3, 4];
+        let mut chunk = items.powerset_chunks(4).remove(1);
+        assert_eq!(0.0, chunk.progress());
+
+        chunk.next();
+        assert_eq!(0.25, chunk.progress());
+        chunk.next_back();
+        assert_eq!(0.5, chunk.progress());
+        chunk.by_ref().for_each(drop);
+        assert_eq!(1.0, chunk.progress());
+
+        let mut nonempty = items.nonempty_powerset();
+        assert_eq!(0.0, nonempty.progress());
+        nonempty.by_ref().for_each(drop);
+        assert_eq!(1.0, nonempty.progress());
+
+        let empty: [i32; 0] = [];
+        assert_eq!(1.0, empty.proper_powerset().progress());
+    }
+
+    #[test]
+    fn largest_supported_container() {
+        let n = usize::BITS as usize - 1;
+        let items = (0..n).collect::<Vec<usize>>();
+        let mut powerset = items.powerset();
+
+        assert_eq!(1 << n, powerset.len());
+        assert_eq!(0, powerset.next().unwrap().count());
+        assert_eq!(
+            items,
+            powerset
+                .next_back()
+                .unwrap()
+                .cloned()
+                .collect::<Vec<usize>>()
+        );
+        assert_eq!((1 << n) - 2, powerset.len());
+
+        // Jump to the end, and make sure the iteration terminates
+        assert_eq!(n - 1, powerset.nth((1 << n) - 3).unwrap().count());
+        assert!(powerset.next().is_none());
+        assert!(powerset.next_back().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "at most")]
+    fn too_large_container() {
+        let items = (0..usize::BITS as usize).collect::<Vec<usize>>();
+        items.powerset();
+    }
+
+    #[test]
+    fn double_ended() {
+        let items = [1, 2, 3, 4];
+
+        let forward = items
+            .powerset()
+            .map(|subset| subset.cloned().collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+        let mut backward = items
+            .powerset()
+            .rev()
+            .map(|subset| subset.cloned().collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        // Alternate between the ends, and check that they meet in the middle
+        let mut powerset = items.powerset();
+        let mut seen = Vec::new();
+        while let Some(front) = powerset.next() {
+            seen.push(front.cloned().collect::<Vec<i32>>());
+            if let Some(back) = powerset.next_back() {
+                seen.push(back.cloned().collect::<Vec<i32>>());
+            }
         }
+        assert!(powerset.next_back().is_none());
+        assert_eq!(16, seen.len());
+        seen.sort();
+        seen.dedup();
+        assert_eq!(16, seen.len());
     }
 }
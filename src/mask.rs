@@ -0,0 +1,87 @@
+//! The integer types that can store the bitmask of a subset.
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::Hash;
+use core::ops::{Add, BitAnd, BitOr, BitXor, Not, Shl, Shr, Sub};
+
+/// The storage of the bitmask backing a [`Subset`](crate::Subset), where bit `i` is set if
+/// element `i` of the container is part of the subset.
+///
+/// It is implemented for all the unsigned integer types, and `usize` is used unless something
+/// else is asked for with [`Powerset::powerset_with`](crate::Powerset::powerset_with). A mask
+/// of `BITS` bits supports containers of at most [`MAX_ELEMENTS`](MaskStorage::MAX_ELEMENTS) elements, so e.g. `u128` allows
+/// containers of up to 127 elements.
+pub trait MaskStorage:
+    Copy
+    + Eq
+    + Ord
+    + Hash
+    + fmt::Debug
+    + fmt::Binary
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+{
+    /// The mask with no bits set
+    const ZERO: Self;
+    /// The mask with only the lowest bit set
+    const ONE: Self;
+    /// The number of bits in the mask
+    const BITS: u32;
+    /// The most elements a container can have with this mask. The mask one past the full
+    /// subset has to fit too, so this is one less than `BITS`.
+    const MAX_ELEMENTS: u32 = Self::BITS - 1;
+
+    fn count_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+    fn leading_zeros(self) -> u32;
+
+    /// Convert from a `usize`, or `None` if it doesn't fit
+    fn from_usize(value: usize) -> Option<Self>;
+    /// Convert to a `usize`, or `None` if it doesn't fit
+    fn to_usize(self) -> Option<usize>;
+
+    /// Check if bit `index` is set. Bits beyond the width of the mask are never set.
+    fn bit_set(self, index: usize) -> bool {
+        index < Self::BITS as usize && (self >> index) & Self::ONE != Self::ZERO
+    }
+}
+
+macro_rules! impl_mask_storage {
+    ($($mask:ty),*) => {
+        $(
+            impl MaskStorage for $mask {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const BITS: u32 = <$mask>::BITS;
+
+                fn count_ones(self) -> u32 {
+                    <$mask>::count_ones(self)
+                }
+
+                fn trailing_zeros(self) -> u32 {
+                    <$mask>::trailing_zeros(self)
+                }
+
+                fn leading_zeros(self) -> u32 {
+                    <$mask>::leading_zeros(self)
+                }
+
+                fn from_usize(value: usize) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+
+                fn to_usize(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_mask_storage!(u8, u16, u32, u64, u128, usize);
@@ -0,0 +1,112 @@
+//! Iteration over the powerset of a container that is owned by the iterator.
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use crate::{assert_supported, SizableContainer, Subset};
+
+/// Like [`Powerset`](crate::Powerset), but the container is moved into the iterator, so it can
+/// outlive the scope the container was created in. The elements are cloned into a new `Vec`
+/// for every subset.
+///
+/// Example usage:
+/// ```
+///     use crate::powerset::IntoPowerset;
+///
+///     fn subsets() -> impl Iterator<Item = Vec<i32>> {
+///         vec![1, 2, 3].into_powerset()
+///     }
+///
+///     assert_eq!(vec![1, 3], subsets().nth(5).unwrap());
+/// ```
+pub trait IntoPowerset<I: Index<usize> + SizableContainer>
+where
+    I::Output: Sized + Clone,
+{
+    fn into_powerset(self) -> OwnedPowersetIterator<I>;
+}
+
+/// The iterator returned from the [`IntoPowerset`](IntoPowerset) trait
+pub struct OwnedPowersetIterator<I: Index<usize>>
+where
+    I::Output: Sized + Clone,
+{
+    items: I,
+    subset: usize,
+    end: usize,
+}
+
+impl<I: Index<usize> + SizableContainer> Iterator for OwnedPowersetIterator<I>
+where
+    I::Output: Sized + Clone,
+{
+    type Item = Vec<I::Output>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.subset >= self.end {
+            return None;
+        }
+
+        self.subset += 1;
+
+        Some(Subset::new(&self.items, self.subset - 1).cloned().collect())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.subset;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<I: Index<usize> + SizableContainer> ExactSizeIterator for OwnedPowersetIterator<I> where
+    I::Output: Sized + Clone
+{
+}
+
+impl<I: Index<usize> + SizableContainer> IntoPowerset<I> for I
+where
+    I::Output: Sized + Clone,
+{
+    fn into_powerset(self) -> OwnedPowersetIterator<I> {
+        assert_supported::<usize>(self.num_elements());
+
+        let end = 1 << self.num_elements();
+        OwnedPowersetIterator {
+            items: self,
+            subset: 0,
+            end,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IntoPowerset, Powerset};
+
+    fn owned_powerset() -> impl Iterator<Item = Vec<String>> {
+        let items = vec![String::from("a"), String::from("b"), String::from("c")];
+        items.into_powerset()
+    }
+
+    #[test]
+    fn outlives_container() {
+        let subsets = owned_powerset().collect::<Vec<_>>();
+
+        assert_eq!(8, subsets.len());
+        assert_eq!(Vec::<String>::new(), subsets[0]);
+        assert_eq!(vec!["a", "c"], subsets[5]);
+        assert_eq!(vec!["a", "b", "c"], subsets[7]);
+    }
+
+    #[test]
+    fn same_as_borrowed() {
+        let items = vec![1, 2, 3, 4];
+
+        assert_eq!(
+            items
+                .powerset()
+                .map(|subset| subset.cloned().collect::<Vec<i32>>())
+                .collect::<Vec<_>>(),
+            items.clone().into_powerset().collect::<Vec<_>>()
+        );
+        assert_eq!(16, items.into_powerset().len());
+    }
+}
@@ -0,0 +1,159 @@
+//! Serialization of subsets with serde.
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::Index;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{Powerset, SizableContainer, Subset};
+
+/// The serialized form of a [`Subset`](crate::Subset).
+///
+/// A subset borrows its container, so only the bitmask (along with the cardinality, for
+/// readability) is serialized. Deserialize into a `SubsetMask`, and use
+/// [`attach`](SubsetMask::attach) to get the subset of a container back.
+///
+/// ```
+///     use crate::powerset::{Powerset, SubsetMask};
+///     let items = [1, 2, 3, 4];
+///     let subset = items.subset_at(0b0110).unwrap();
+///
+///     let json = serde_json::to_string(&subset).unwrap();
+///     let mask: SubsetMask = serde_json::from_str(&json).unwrap();
+///
+///     assert_eq!(vec![2, 3], mask.attach(&items).unwrap().cloned().collect::<Vec<i32>>());
+/// ```
+///
+/// Deserializing fails if the cardinality doesn't match the number of bits set in the mask.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedSubsetMask")]
+pub struct SubsetMask {
+    mask: usize,
+    cardinality: usize,
+}
+
+// The fields as they are deserialized, before the cardinality is checked against the mask
+#[derive(Deserialize)]
+struct UncheckedSubsetMask {
+    mask: usize,
+    cardinality: usize,
+}
+
+// The error when the cardinality of a deserialized subset doesn't match its mask
+struct CardinalityMismatch {
+    mask: usize,
+    cardinality: usize,
+}
+
+impl fmt::Display for CardinalityMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the mask {:#b} has {} elements, not {}",
+            self.mask,
+            self.mask.count_ones(),
+            self.cardinality
+        )
+    }
+}
+
+impl TryFrom<UncheckedSubsetMask> for SubsetMask {
+    type Error = CardinalityMismatch;
+
+    fn try_from(unchecked: UncheckedSubsetMask) -> Result<Self, Self::Error> {
+        let UncheckedSubsetMask { mask, cardinality } = unchecked;
+        if mask.count_ones() as usize != cardinality {
+            return Err(CardinalityMismatch { mask, cardinality });
+        }
+
+        Ok(SubsetMask { mask, cardinality })
+    }
+}
+
+impl SubsetMask {
+    /// The bitmask of the serialized subset.
+    pub fn mask(&self) -> usize {
+        self.mask
+    }
+
+    /// The number of elements in the serialized subset.
+    pub fn cardinality(&self) -> usize {
+        self.cardinality
+    }
+
+    /// Rebuild the subset over `items`. Returns `None` if the mask selects elements beyond the
+    /// end of the container.
+    pub fn attach<'a, I: Index<usize> + SizableContainer + ?Sized>(
+        &self,
+        items: &'a I,
+    ) -> Option<Subset<'a, I>>
+    where
+        I::Output: Sized,
+    {
+        items.subset_at(self.mask)
+    }
+}
+
+impl<'a, I: Index<usize> + ?Sized> From<&Subset<'a, I>> for SubsetMask {
+    fn from(subset: &Subset<'a, I>) -> Self {
+        SubsetMask {
+            mask: subset.bitmask(),
+            cardinality: subset.cardinality(),
+        }
+    }
+}
+
+impl<'a, I: Index<usize> + ?Sized> Serialize for Subset<'a, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SubsetMask::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Powerset, SubsetMask};
+
+    #[test]
+    fn serialize_subset() {
+        let items = [1, 2, 3, 4];
+        let subset = items.subset_at(0b1011).unwrap();
+
+        assert_eq!(
+            r#"{"mask":11,"cardinality":3}"#,
+            serde_json::to_string(&subset).unwrap()
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let items = [1, 2, 3, 4];
+
+        for subset in items.powerset() {
+            let json = serde_json::to_string(&subset).unwrap();
+            let mask: SubsetMask = serde_json::from_str(&json).unwrap();
+            assert_eq!(subset.bitmask(), mask.mask());
+            assert_eq!(subset.cardinality(), mask.cardinality());
+
+            let rebuilt = mask.attach(&items).unwrap();
+            assert_eq!(
+                subset.cloned().collect::<Vec<i32>>(),
+                rebuilt.cloned().collect::<Vec<i32>>()
+            );
+        }
+
+        let mask: SubsetMask = serde_json::from_str(r#"{"mask":16,"cardinality":1}"#).unwrap();
+        assert!(mask.attach(&items).is_none());
+    }
+
+    #[test]
+    fn wrong_cardinality() {
+        let error = serde_json::from_str::<SubsetMask>(r#"{"mask":3,"cardinality":5}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("the mask 0b11 has 2 elements, not 5"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Iteration over the powerset of small arrays, with the narrowest mask that fits.
+use crate::{MaskStorage, Powerset, SubsetIterator};
+
+/// A marker type carrying the number of elements of an array, to pick a mask width for it.
+pub struct Const<const N: usize>;
+
+/// Picks the narrowest [`MaskStorage`](MaskStorage) that supports `N` elements, for
+/// [`SmallPowerset`](SmallPowerset). It is implemented for `Const<N>` where `N <= 64`.
+pub trait MaskWidth {
+    /// The mask used for the subsets
+    type Mask: MaskStorage;
+}
+
+macro_rules! impl_mask_width {
+    ($mask:ty: $($n:literal)*) => {
+        $(
+            impl MaskWidth for Const<$n> {
+                type Mask = $mask;
+            }
+        )*
+    };
+}
+
+impl_mask_width!(u8: 0 1 2 3 4 5 6 7);
+impl_mask_width!(u16: 8 9 10 11 12 13 14 15);
+impl_mask_width!(u32: 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31);
+impl_mask_width!(u64:
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47
+    48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63);
+impl_mask_width!(u128: 64);
+
+/// The iterator returned from [`SmallPowerset::powerset_small`](SmallPowerset::powerset_small)
+pub type SmallPowersetIterator<'a, I, const N: usize> =
+    SubsetIterator<'a, I, <Const<N> as MaskWidth>::Mask>;
+
+/// The powerset of an array, where the number of elements is known at compile time.
+pub trait SmallPowerset<'a, T, const N: usize>
+where
+    Const<N>: MaskWidth,
+{
+    /// Like [`powerset`](Powerset::powerset), but the subsets are backed by the narrowest
+    /// mask that fits `N` elements, e.g. a `u8` for at most 7 elements.
+    fn powerset_small(&'a self) -> SmallPowersetIterator<'a, [T; N], N>;
+}
+
+impl<'a, T: 'a, const N: usize> SmallPowerset<'a, T, N> for [T; N]
+where
+    Const<N>: MaskWidth,
+{
+    fn powerset_small(&'a self) -> SmallPowersetIterator<'a, [T; N], N> {
+        self.powerset_with()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn same_as_powerset() {
+        let items = ['a', 'b', 'c', 'd'];
+
+        let small = items.powerset_small();
+        assert_eq!(16, small.len());
+        assert_eq!(16, small.clone().count());
+        assert!(small
+            .map(|subset| (
+                subset.bitmask() as usize,
+                subset.cloned().collect::<Vec<_>>()
+            ))
+            .eq(items
+                .powerset()
+                .map(|subset| (subset.bitmask(), subset.to_vec()))));
+    }
+
+    #[test]
+    fn len() {
+        let mut small = [0; 7].powerset_small();
+        assert_eq!(128, small.len());
+        small.next();
+        small.next_back();
+        assert_eq!(126, small.len());
+
+        assert_eq!(256, [0; 8].powerset_small().len());
+        assert_eq!(1 << 31, [0; 31].powerset_small().len());
+    }
+
+    #[test]
+    fn mask_width() {
+        let _: SubsetIterator<'_, _, u8> = [0; 7].powerset_small();
+        let _: SubsetIterator<'_, _, u16> = [0; 8].powerset_small();
+        let _: SubsetIterator<'_, _, u32> = [0; 31].powerset_small();
+        let _: SubsetIterator<'_, _, u64> = [0; 63].powerset_small();
+
+        let full = [0; 64].powerset_small().next_back().unwrap();
+        assert_eq!(64, full.count());
+    }
+}